@@ -0,0 +1,303 @@
+//! Color Space Conversion
+//!
+//! sRGB ↔ linear transfer functions for gamma-correct color math, plus
+//! whole-image color utilities (grayscale, per-band average colors).
+//! Decoding goes through a 256-entry lookup table, since every 8-bit input
+//! maps to one of only 256 linear values.
+//!
+//! Also home to [`to_u8`], the one float → byte conversion used throughout
+//! the crate.
+
+use std::sync::OnceLock;
+
+use wasm_bindgen::prelude::*;
+
+use crate::error::{check_len, Error};
+
+/// Lookup table of `srgb_to_linear` for every byte value
+fn srgb_lut() -> &'static [f32; 256] {
+    static LUT: OnceLock<[f32; 256]> = OnceLock::new();
+    LUT.get_or_init(|| {
+        let mut lut = [0.0f32; 256];
+        for (i, v) in lut.iter_mut().enumerate() {
+            *v = decode_srgb(i as f32 / 255.0);
+        }
+        lut
+    })
+}
+
+/// sRGB electro-optical transfer function (0.0-1.0 in, 0.0-1.0 out)
+pub(crate) fn decode_srgb(v: f32) -> f32 {
+    if v <= 0.04045 {
+        v / 12.92
+    } else {
+        ((v + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Convert sRGB to linear color space
+pub(crate) fn srgb_to_linear(value: u8) -> f32 {
+    srgb_lut()[value as usize]
+}
+
+/// Convert linear to sRGB color space
+pub(crate) fn linear_to_srgb(value: f32) -> u8 {
+    to_u8(encode_srgb(value) * 255.0)
+}
+
+/// Inverse of [`decode_srgb`] (0.0-1.0 in, 0.0-1.0 out)
+pub(crate) fn encode_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Quantize a 0-255 float to a byte
+///
+/// Every float → byte conversion in the crate goes through here, so all
+/// code paths agree bit for bit. The value is clamped to 0-255 and rounded
+/// half-up (`127.5` → 128, `0.5` → 1). `f32::round` is specified exactly by
+/// Rust rather than by the FPU's rounding mode, so the result is the same
+/// on every platform. NaN maps to 0.
+#[inline]
+pub(crate) fn to_u8(value: f32) -> u8 {
+    value.clamp(0.0, 255.0).round() as u8
+}
+
+/// Quantize a 0-65535 float to a 16-bit value, rounding like [`to_u8`]
+#[inline]
+pub(crate) fn to_u16(value: f32) -> u16 {
+    value.clamp(0.0, 65535.0).round() as u16
+}
+
+/// Convert a buffer of sRGB bytes to linear floats (0.0-1.0)
+///
+/// Operates on every byte, so RGBA input converts alpha too; skip every
+/// fourth value if alpha should stay linear.
+#[wasm_bindgen]
+pub fn srgb_to_linear_slice(data: &[u8]) -> Vec<f32> {
+    let lut = srgb_lut();
+    data.iter().map(|&v| lut[v as usize]).collect()
+}
+
+/// Convert a buffer of linear floats (0.0-1.0) to sRGB bytes
+///
+/// Values outside 0.0-1.0 are clamped.
+#[wasm_bindgen]
+pub fn linear_to_srgb_slice(data: &[f32]) -> Vec<u8> {
+    data.iter().map(|&v| linear_to_srgb(v)).collect()
+}
+
+/// Convert RGBA to grayscale, preserving alpha
+///
+/// Gray is Rec. 709 luminance (`0.2126 R + 0.7152 G + 0.0722 B`), so
+/// green reads much brighter than blue as it does to the eye. With
+/// `gamma_correct` the weights are applied to linear light and the result
+/// re-encoded to sRGB; without it they are applied to the sRGB bytes
+/// directly (luma), which is cheaper but darkens saturated colors.
+///
+/// # Arguments
+///
+/// * `image_data` - RGBA pixel data (straight alpha)
+/// * `width` - Image width
+/// * `height` - Image height
+/// * `gamma_correct` - Weight in linear light rather than sRGB
+///
+/// # Returns
+///
+/// RGBA with R = G = B = luminance and the original alpha
+///
+/// # Errors
+///
+/// Returns [`Error::LengthMismatch`] if `image_data` doesn't match the
+/// dimensions.
+#[wasm_bindgen]
+pub fn to_grayscale(
+    image_data: &[u8],
+    width: u32,
+    height: u32,
+    gamma_correct: bool,
+) -> Result<Vec<u8>, Error> {
+    check_len("image_data", image_data.len(), width as usize * height as usize * 4)?;
+    let mut result = image_data.to_vec();
+
+    for px in result.chunks_exact_mut(4) {
+        let gray = if gamma_correct {
+            linear_to_srgb(
+                0.2126 * srgb_to_linear(px[0])
+                    + 0.7152 * srgb_to_linear(px[1])
+                    + 0.0722 * srgb_to_linear(px[2]),
+            )
+        } else {
+            to_u8(0.2126 * px[0] as f32 + 0.7152 * px[1] as f32 + 0.0722 * px[2] as f32)
+        };
+        px[..3].fill(gray);
+    }
+
+    Ok(result)
+}
+
+/// Alpha-weighted average color of each horizontal band of an image
+///
+/// Splits the rows into `regions` bands of near-equal height (e.g. 3 for
+/// top, middle and bottom) and averages each one for palette extraction.
+/// Colors are averaged in linear light and weighted by alpha, as if
+/// averaged premultiplied and then unpremultiplied, so transparent pixels
+/// never pull the result toward their (usually black) RGB and mixed bands
+/// don't come out darker than they look.
+///
+/// # Arguments
+///
+/// * `image_data` - RGBA pixel data (straight alpha)
+/// * `width` - Image width
+/// * `height` - Image height
+/// * `regions` - Number of bands, top to bottom
+///
+/// # Returns
+///
+/// One packed `0xRRGGBBAA` color per band, alpha being the band's mean
+/// alpha. A fully transparent (or empty) band is 0.
+///
+/// # Errors
+///
+/// Returns [`Error::LengthMismatch`] if `image_data` doesn't match the
+/// dimensions.
+#[wasm_bindgen]
+pub fn region_average_colors(
+    image_data: &[u8],
+    width: u32,
+    height: u32,
+    regions: u32,
+) -> Result<Vec<u32>, Error> {
+    let w = width as usize;
+    let h = height as usize;
+    check_len("image_data", image_data.len(), w * h * 4)?;
+    let regions = regions as usize;
+
+    Ok((0..regions)
+        .map(|band| {
+            let rows = band * h / regions..(band + 1) * h / regions;
+            average_color(&image_data[rows.start * w * 4..rows.end * w * 4])
+        })
+        .collect())
+}
+
+/// Alpha-weighted linear-light average of RGBA pixels, packed as
+/// `0xRRGGBBAA`
+fn average_color(pixels: &[u8]) -> u32 {
+    let mut sums = [0f64; 3];
+    let mut alpha = 0u64;
+    for px in pixels.chunks_exact(4) {
+        let a = px[3] as u64;
+        for (sum, &c) in sums.iter_mut().zip(px) {
+            *sum += srgb_to_linear(c) as f64 * a as f64;
+        }
+        alpha += a;
+    }
+    if alpha == 0 {
+        return 0;
+    }
+
+    let count = (pixels.len() / 4) as f64;
+    let channel = |sum: f64| linear_to_srgb((sum / alpha as f64) as f32);
+    u32::from_be_bytes([
+        channel(sums[0]),
+        channel(sums[1]),
+        channel(sums[2]),
+        to_u8((alpha as f64 / count) as f32),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        let linear = srgb_to_linear_slice(&bytes);
+        let back = linear_to_srgb_slice(&linear);
+
+        for (original, recovered) in bytes.iter().zip(back.iter()) {
+            assert!((*original as i32 - *recovered as i32).abs() <= 1);
+        }
+
+        assert_eq!(linear[0], 0.0);
+        assert!((linear[255] - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_to_u8_rounds_half_up() {
+        assert_eq!(to_u8(0.5), 1);
+        assert_eq!(to_u8(1.5), 2);
+        assert_eq!(to_u8(2.5), 3);
+        assert_eq!(to_u8(127.5), 128);
+        assert_eq!(to_u8(254.5), 255);
+        assert_eq!(to_u8(127.49), 127);
+
+        // Clamped at both ends
+        assert_eq!(to_u8(-3.0), 0);
+        assert_eq!(to_u8(300.0), 255);
+        assert_eq!(to_u8(f32::NAN), 0);
+    }
+
+    #[test]
+    fn test_grayscale_rec709() {
+        let image = [255, 0, 0, 200, 0, 255, 0, 255, 255, 255, 255, 10, 0, 0, 0, 0];
+
+        // Pure red carries 21% of white's luminance, well below the 85 of
+        // a plain channel average
+        let luma = to_grayscale(&image, 4, 1, false).unwrap();
+        assert_eq!(luma[..4], [54, 54, 54, 200]);
+        assert_eq!(luma[4], 182);
+        assert_eq!(luma[8..], [255, 255, 255, 10, 0, 0, 0, 0]);
+
+        // In linear light, then re-encoded
+        let linear = to_grayscale(&image, 4, 1, true).unwrap();
+        assert_eq!(linear[..4], [127, 127, 127, 200]);
+        assert_eq!(linear[0], linear_to_srgb(0.2126));
+        assert_eq!(linear[8..], [255, 255, 255, 10, 0, 0, 0, 0]);
+
+        let result = to_grayscale(&image[..8], 4, 1, true);
+        assert!(matches!(result, Err(Error::LengthMismatch { .. })));
+    }
+
+    #[test]
+    fn test_region_average_colors() {
+        // Red top half, blue bottom half, with a transparent black column
+        // that must not darken either band
+        let (w, h) = (6usize, 8usize);
+        let image: Vec<u8> = (0..w * h)
+            .flat_map(|i| {
+                if i % w == 0 {
+                    [0, 0, 0, 0]
+                } else if i / w < h / 2 {
+                    [255, 0, 0, 255]
+                } else {
+                    [0, 0, 255, 255]
+                }
+            })
+            .collect();
+
+        let colors = region_average_colors(&image, w as u32, h as u32, 2).unwrap();
+        assert_eq!(colors.len(), 2);
+        assert_eq!(colors[0] >> 8, 0xff0000);
+        assert_eq!(colors[1] >> 8, 0x0000ff);
+        assert_eq!(colors[0] & 0xff, 213); // 5 of 6 pixels opaque
+
+        // Thirds (rows 0-1, 2-4, 5-7): the middle band is two red rows
+        // and one blue, mixed 2:1 in linear light (averaging the sRGB bytes
+        // would give the darker 0xaa0055)
+        let thirds = region_average_colors(&image, w as u32, h as u32, 3).unwrap();
+        assert_eq!(thirds[0] >> 8, 0xff0000);
+        assert_eq!(thirds[1] >> 8, 0xd5009c);
+        assert_eq!(thirds[2] >> 8, 0x0000ff);
+
+        assert!(region_average_colors(&image, w as u32, h as u32, 0).unwrap().is_empty());
+
+        let result = region_average_colors(&image[4..], w as u32, h as u32, 2);
+        assert!(matches!(result, Err(Error::LengthMismatch { .. })));
+    }
+}
@@ -0,0 +1,270 @@
+//! Compositing
+//!
+//! Pasting one sprite's pixels into another frame so the result doesn't
+//! show where the two images meet, and crossfading between two frames.
+
+use wasm_bindgen::prelude::*;
+
+use crate::color::to_u8;
+use crate::error::{check_len, Error};
+use crate::OPAQUE_THRESHOLD;
+
+/// Jacobi iterations run by [`poisson_blend`]
+const POISSON_ITERATIONS: usize = 200;
+
+/// Paste a masked source region into a target with a seamless clone
+///
+/// Gradient-domain (Poisson) blending: inside the pasted region the result
+/// keeps the source's color gradients but is solved so that it meets the
+/// target's colors at the region boundary, absorbing differences in
+/// lighting and tint. The solve starts from the source shifted by its mean
+/// mismatch with the target along the boundary, so a constant difference
+/// is absorbed immediately, then runs a fixed number of Jacobi iterations
+/// to spread any remaining smooth residual.
+///
+/// RGB is blended; alpha inside the region is taken from the source.
+///
+/// # Arguments
+///
+/// * `target` - RGBA pixel data to paste into, modified in place
+/// * `source` - RGBA pixel data to paste from, same size as `target`
+/// * `mask` - Source pixels to paste (alpha >= [`OPAQUE_THRESHOLD`]), same size as `target`
+/// * `width` - Image width
+/// * `height` - Image height
+/// * `offset_x`, `offset_y` - Where the source's origin lands in the target
+///
+/// # Errors
+///
+/// Returns [`Error::LengthMismatch`] if a buffer doesn't match the
+/// dimensions.
+#[wasm_bindgen]
+pub fn poisson_blend(
+    target: &mut [u8],
+    source: &[u8],
+    mask: &[u8],
+    width: u32,
+    height: u32,
+    offset_x: i32,
+    offset_y: i32,
+) -> Result<(), Error> {
+    let w = width as usize;
+    let h = height as usize;
+    check_len("target", target.len(), w * h * 4)?;
+    check_len("source", source.len(), w * h * 4)?;
+    check_len("mask", mask.len(), w * h)?;
+
+    // Source pixel under a target pixel, if it exists
+    let source_at = |x: usize, y: usize| -> Option<usize> {
+        let sx = x as i64 - offset_x as i64;
+        let sy = y as i64 - offset_y as i64;
+        if sx < 0 || sy < 0 || sx >= w as i64 || sy >= h as i64 {
+            None
+        } else {
+            Some(sy as usize * w + sx as usize)
+        }
+    };
+
+    // Pasted region: (target index, source index) pairs
+    let mut inside = vec![false; w * h];
+    let mut region = Vec::new();
+    for y in 0..h {
+        for x in 0..w {
+            if let Some(s) = source_at(x, y).filter(|&s| mask[s] >= OPAQUE_THRESHOLD) {
+                inside[y * w + x] = true;
+                region.push((y * w + x, s));
+            }
+        }
+    }
+    if region.is_empty() {
+        return Ok(());
+    }
+
+    let neighbors = |idx: usize| {
+        let (x, y) = (idx % w, idx / w);
+        [
+            (x > 0).then(|| idx - 1),
+            (x + 1 < w).then(|| idx + 1),
+            (y > 0).then(|| idx - w),
+            (y + 1 < h).then(|| idx + w),
+        ]
+        .into_iter()
+        .flatten()
+    };
+    let rgb = |data: &[u8], idx: usize| -> [f32; 3] {
+        [
+            data[idx * 4] as f32,
+            data[idx * 4 + 1] as f32,
+            data[idx * 4 + 2] as f32,
+        ]
+    };
+
+    // Mean target-minus-source mismatch across the boundary
+    let mut offset = [0.0f32; 3];
+    let mut pairs = 0usize;
+    for &(t, s) in &region {
+        for q in neighbors(t).filter(|&q| !inside[q]) {
+            let (outer, own) = (rgb(target, q), rgb(source, s));
+            for c in 0..3 {
+                offset[c] += outer[c] - own[c];
+            }
+            pairs += 1;
+        }
+    }
+    if pairs > 0 {
+        for value in &mut offset {
+            *value /= pairs as f32;
+        }
+    }
+
+    let mut solution = vec![[0.0f32; 3]; w * h];
+    for &(t, s) in &region {
+        let own = rgb(source, s);
+        solution[t] = [own[0] + offset[0], own[1] + offset[1], own[2] + offset[2]];
+    }
+
+    // Jacobi: each pixel becomes the mean of its neighbors plus the
+    // source's gradient toward them, with the target fixed outside
+    let mut next = solution.clone();
+    for _ in 0..POISSON_ITERATIONS {
+        for &(t, s) in &region {
+            let own = rgb(source, s);
+            let mut sum = [0.0f32; 3];
+            let mut count = 0.0f32;
+
+            for q in neighbors(t) {
+                let guide = source_at(q % w, q / w).map_or(own, |sq| rgb(source, sq));
+                let value = if inside[q] { solution[q] } else { rgb(target, q) };
+                for c in 0..3 {
+                    sum[c] += value[c] + own[c] - guide[c];
+                }
+                count += 1.0;
+            }
+
+            next[t] = [sum[0] / count, sum[1] / count, sum[2] / count];
+        }
+        std::mem::swap(&mut solution, &mut next);
+    }
+
+    for &(t, s) in &region {
+        for c in 0..3 {
+            target[t * 4 + c] = to_u8(solution[t][c]);
+        }
+        target[t * 4 + 3] = source[s * 4 + 3];
+    }
+
+    Ok(())
+}
+
+/// Crossfade between two RGBA images
+///
+/// Linear interpolation from `a` (`t = 0`) to `b` (`t = 1`). The blend is
+/// done on premultiplied color either way: with straight alpha, fading a
+/// sprite toward transparency would otherwise pull its edges toward the
+/// (usually black) color of transparent pixels. Straight input is
+/// premultiplied, blended and unpremultiplied back.
+///
+/// # Arguments
+///
+/// * `a` - RGBA pixel data at `t = 0`
+/// * `b` - RGBA pixel data at `t = 1`, same length as `a`
+/// * `t` - Blend position, clamped to 0.0-1.0
+/// * `premultiplied` - Whether both inputs (and the output) are premultiplied
+///
+/// # Errors
+///
+/// Returns [`Error::LengthMismatch`] if the buffers differ in length.
+#[wasm_bindgen]
+pub fn crossfade(a: &[u8], b: &[u8], t: f32, premultiplied: bool) -> Result<Vec<u8>, Error> {
+    check_len("b", b.len(), a.len())?;
+    let t = t.clamp(0.0, 1.0);
+
+    let mut result = vec![0u8; a.len()];
+    for ((out, pa), pb) in result
+        .chunks_exact_mut(4)
+        .zip(a.chunks_exact(4))
+        .zip(b.chunks_exact(4))
+    {
+        let alpha = pa[3] as f32 + (pb[3] as f32 - pa[3] as f32) * t;
+        out[3] = to_u8(alpha);
+
+        for c in 0..3 {
+            if premultiplied {
+                out[c] = to_u8(pa[c] as f32 + (pb[c] as f32 - pa[c] as f32) * t);
+            } else {
+                let ca = pa[c] as f32 * pa[3] as f32;
+                let cb = pb[c] as f32 * pb[3] as f32;
+                let blended = ca + (cb - ca) * t;
+                out[c] = if alpha > 0.0 { to_u8(blended / alpha) } else { 0 };
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_poisson_constant_offset() {
+        // Horizontal gradient target; the source is the same gradient 60
+        // levels brighter, as if lit differently
+        let (w, h) = (32usize, 16usize);
+        let gradient = |lift: usize| -> Vec<u8> {
+            (0..w * h)
+                .flat_map(|i| {
+                    let v = (20 + 4 * (i % w) + lift) as u8;
+                    [v, v, v, 255]
+                })
+                .collect()
+        };
+        let source = gradient(60);
+        let mut mask = vec![0u8; w * h];
+        for y in 4..12 {
+            for x in 4..12 {
+                mask[y * w + x] = 255;
+            }
+        }
+
+        // Paste the square 10px to the right
+        let original = gradient(0);
+        let mut target = original.clone();
+        poisson_blend(&mut target, &source, &mask, w as u32, h as u32, 10, 0).unwrap();
+
+        // The pasted square takes on the target's lighting: the row stays
+        // one smooth ramp straight through both seams
+        let row: Vec<i32> = (0..w).map(|x| target[(8 * w + x) * 4] as i32).collect();
+        for x in 0..w {
+            assert!((row[x] - original[(8 * w + x) * 4] as i32).abs() <= 1, "x = {}", x);
+        }
+        for x in 12..24 {
+            assert!((row[x + 1] - row[x] - 4).abs() <= 1, "seam step at x = {}", x);
+        }
+
+        // Not a plain paste, which would jump 24 levels at the left seam
+        assert_eq!(source[(8 * w + 4) * 4] as i32 - row[13], 24);
+        assert!((row[14] - row[13] - 4).abs() <= 1);
+        assert!(poisson_blend(&mut target, &source, &mask[1..], w as u32, h as u32, 0, 0).is_err());
+    }
+
+    #[test]
+    fn test_crossfade() {
+        let red = [255, 0, 0, 255, 255, 0, 0, 255];
+        let blue = [0, 0, 255, 255, 0, 0, 0, 0];
+
+        for premultiplied in [false, true] {
+            assert_eq!(crossfade(&red, &blue, 0.0, premultiplied).unwrap(), red);
+            assert_eq!(crossfade(&red, &blue, 1.0, premultiplied).unwrap(), blue);
+        }
+
+        // Halfway: purple where both are opaque; where `b` is transparent,
+        // straight alpha keeps the color red instead of darkening it
+        let straight = crossfade(&red, &blue, 0.5, false).unwrap();
+        assert_eq!(straight, [128, 0, 128, 255, 255, 0, 0, 128]);
+        let premul = crossfade(&red, &blue, 0.5, true).unwrap();
+        assert_eq!(premul, [128, 0, 128, 255, 128, 0, 0, 128]);
+
+        assert!(crossfade(&red, &blue[..4], 0.5, false).is_err());
+    }
+}
@@ -0,0 +1,684 @@
+//! SDF Effects
+//!
+//! Masks derived from an encoded Signed Distance Field (as produced by
+//! [`generate_sdf`](crate::sdf::generate_sdf)) for outline, glow and shading
+//! effects. Effects that need more precision than the 8-bit encoding take
+//! the alpha mask and compute distances directly.
+//!
+//! Edge seeds sit on the pixels either side of an alpha transition, so the
+//! true silhouette lies half a pixel beyond each seed. Effects account for
+//! this offset so widths are measured from the silhouette itself.
+
+use wasm_bindgen::prelude::*;
+
+use crate::color::to_u8;
+use crate::sdf::{decode_distance, jump_flood, seed_distances, SdfOptions};
+
+/// Render an anti-aliased stroke following the SDF's zero-crossing
+///
+/// # Arguments
+///
+/// * `sdf` - Encoded SDF (128 = edge)
+/// * `width` - Image width
+/// * `height` - Image height
+/// * `stroke_width` - Total stroke width in pixels, centered on the silhouette
+/// * `max_distance` - The `max_distance` the SDF was generated with
+///
+/// # Returns
+///
+/// Alpha mask, 255 within `stroke_width / 2` of the silhouette with a
+/// one-pixel linear falloff at the band's borders
+#[wasm_bindgen]
+pub fn sdf_stroke(
+    sdf: &[u8],
+    width: u32,
+    height: u32,
+    stroke_width: f32,
+    max_distance: f32,
+) -> Vec<u8> {
+    let size = width as usize * height as usize;
+    let half = stroke_width * 0.5;
+
+    let mut result = vec![0u8; size];
+    for i in 0..size {
+        // Distance from the silhouette to this pixel's center
+        let dist = decode_distance(sdf[i], max_distance).abs() + 0.5;
+
+        // Fraction of the pixel covered by the band
+        let coverage = (half - dist + 0.5).clamp(0.0, 1.0);
+        result[i] = to_u8(coverage * 255.0);
+    }
+
+    result
+}
+
+/// Draw distance contours of an SDF for debugging
+///
+/// Produces a contour-map visualization: one-pixel anti-aliased lines
+/// wherever the distance from the silhouette is a multiple of `spacing`,
+/// inside and outside alike. The zero contour is the silhouette itself.
+///
+/// # Arguments
+///
+/// * `sdf` - Encoded SDF (128 = edge)
+/// * `width` - Image width
+/// * `height` - Image height
+/// * `spacing` - Distance between contours in pixels
+/// * `max_distance` - The `max_distance` the SDF was generated with
+///
+/// # Returns
+///
+/// Line mask; all zeros if `spacing` is 0 or less
+#[wasm_bindgen]
+pub fn sdf_isolines(
+    sdf: &[u8],
+    width: u32,
+    height: u32,
+    spacing: f32,
+    max_distance: f32,
+) -> Vec<u8> {
+    let size = width as usize * height as usize;
+    let mut result = vec![0u8; size];
+    if spacing <= 0.0 {
+        return result;
+    }
+
+    for i in 0..size {
+        let dist = decode_distance(sdf[i], max_distance).abs() + 0.5;
+
+        // Offset from the nearest contour, then a one-pixel-wide band
+        let offset = (dist - (dist / spacing).round() * spacing).abs();
+        let coverage = (1.0 - offset).clamp(0.0, 1.0);
+        result[i] = to_u8(coverage * 255.0);
+    }
+
+    result
+}
+
+/// Render an animated dashed stroke ("marching ants") along the silhouette
+///
+/// The stroke band matches [`sdf_stroke`]. To lay dashes along it, the
+/// inner contour (edge pixels just inside the silhouette) is traced into
+/// chains, giving each contour pixel its arc length from the start of its
+/// chain; every band pixel then takes the arc length of its nearest contour
+/// pixel via a jump flood. Closed shapes have a seam where the chain starts
+/// and ends, at the contour's top-left pixel.
+///
+/// # Arguments
+///
+/// * `sdf` - Encoded SDF (128 = edge)
+/// * `width` - Image width
+/// * `height` - Image height
+/// * `dash_length` - Length of each dash and of each gap, in pixels
+/// * `phase` - Offset along the contour in pixels; advance it per frame to
+///   animate
+/// * `stroke_width` - Total stroke width in pixels, centered on the silhouette
+/// * `max_distance` - The `max_distance` the SDF was generated with
+///
+/// # Returns
+///
+/// Alpha mask of the dashes; a solid [`sdf_stroke`] if `dash_length` is 0 or
+/// less
+#[wasm_bindgen]
+pub fn dashed_outline(
+    sdf: &[u8],
+    width: u32,
+    height: u32,
+    dash_length: f32,
+    phase: f32,
+    stroke_width: f32,
+    max_distance: f32,
+) -> Vec<u8> {
+    let stroke = sdf_stroke(sdf, width, height, stroke_width, max_distance);
+    if dash_length <= 0.0 {
+        return stroke;
+    }
+
+    let w = width as usize;
+    let h = height as usize;
+    let dist: Vec<f32> = sdf[..w * h]
+        .iter()
+        .map(|&v| decode_distance(v, max_distance))
+        .collect();
+
+    // Inner contour: edge pixels with a strictly interior 8-neighbor
+    let contour: Vec<bool> = (0..w * h)
+        .map(|i| {
+            let (x, y) = (i % w, i / w);
+            let xs = x.saturating_sub(1)..(x + 2).min(w);
+            dist[i].abs() < 0.5
+                && (y.saturating_sub(1)..(y + 2).min(h))
+                    .any(|ny| xs.clone().any(|nx| dist[ny * w + nx] < -0.5))
+        })
+        .collect();
+
+    // Walk each chain, preferring axis-aligned steps over diagonal ones
+    const STEPS: [(i32, i32, f32); 8] = [
+        (1, 0, 1.0),
+        (0, 1, 1.0),
+        (-1, 0, 1.0),
+        (0, -1, 1.0),
+        (1, 1, std::f32::consts::SQRT_2),
+        (-1, 1, std::f32::consts::SQRT_2),
+        (-1, -1, std::f32::consts::SQRT_2),
+        (1, -1, std::f32::consts::SQRT_2),
+    ];
+    let mut arc = vec![0.0f32; w * h];
+    let mut visited = vec![false; w * h];
+    let mut seeds = vec![-1i32; w * h];
+    let mut distances = vec![f32::MAX; w * h];
+
+    for start in 0..w * h {
+        if !contour[start] || visited[start] {
+            continue;
+        }
+
+        let (mut idx, mut length) = (start, 0.0f32);
+        loop {
+            visited[idx] = true;
+            arc[idx] = length;
+            seeds[idx] = idx as i32;
+            distances[idx] = 0.0;
+
+            let (x, y) = ((idx % w) as i32, (idx / w) as i32);
+            let next = STEPS.iter().find_map(|&(dx, dy, cost)| {
+                let (nx, ny) = (x + dx, y + dy);
+                if nx < 0 || ny < 0 || nx >= w as i32 || ny >= h as i32 {
+                    return None;
+                }
+                let n = ny as usize * w + nx as usize;
+                (contour[n] && !visited[n]).then_some((n, cost))
+            });
+
+            match next {
+                Some((n, cost)) => {
+                    idx = n;
+                    length += cost;
+                }
+                None => break,
+            }
+        }
+    }
+
+    jump_flood(w, h, &SdfOptions::default(), &[], &mut seeds, &mut distances);
+
+    stroke
+        .iter()
+        .zip(&seeds)
+        .map(|(&coverage, &seed)| {
+            if seed < 0 {
+                return 0;
+            }
+            let position = (arc[seed as usize] + phase) / dash_length;
+            if position.rem_euclid(2.0) < 1.0 { coverage } else { 0 }
+        })
+        .collect()
+}
+
+/// Render an SDF stroke that widens where the silhouette curves sharply
+///
+/// Like [`sdf_stroke`], but the half-width at each pixel grows with the
+/// local curvature of the distance field's isolines, so tight convex
+/// corners don't look pinched. Curvature is estimated from second
+/// differences as the field's second derivative along the isoline
+/// (perpendicular to the gradient); that term is zero along straight edges
+/// and `1 / r` around a corner at radius `r`.
+///
+/// # Arguments
+///
+/// * `sdf` - Encoded SDF (128 = edge)
+/// * `width` - Image width
+/// * `height` - Image height
+/// * `stroke_width` - Stroke width in pixels along straight edges
+/// * `max_distance` - The `max_distance` the SDF was generated with
+/// * `corner_boost` - Extra width at high curvature, as a fraction of
+///   `stroke_width` (0 behaves like [`sdf_stroke`])
+#[wasm_bindgen]
+pub fn sdf_stroke_adaptive(
+    sdf: &[u8],
+    width: u32,
+    height: u32,
+    stroke_width: f32,
+    max_distance: f32,
+    corner_boost: f32,
+) -> Vec<u8> {
+    let w = width as usize;
+    let h = height as usize;
+    let half = stroke_width * 0.5;
+
+    // Unsigned distance from the silhouette at each pixel center
+    let dist: Vec<f32> = sdf
+        .iter()
+        .map(|&v| decode_distance(v, max_distance).abs() + 0.5)
+        .collect();
+    let at = |x: usize, y: usize| dist[y * w + x];
+
+    let mut result = vec![0u8; w * h];
+    for y in 0..h {
+        for x in 0..w {
+            let d = at(x, y);
+            let mut local_half = half;
+
+            if x > 0 && y > 0 && x + 1 < w && y + 1 < h {
+                let curvature = isoline_curvature([
+                    [at(x - 1, y - 1), at(x, y - 1), at(x + 1, y - 1)],
+                    [at(x - 1, y), d, at(x + 1, y)],
+                    [at(x - 1, y + 1), at(x, y + 1), at(x + 1, y + 1)],
+                ]);
+                local_half += half * corner_boost * (curvature * half).min(1.0);
+            }
+
+            let coverage = (local_half - d + 0.5).clamp(0.0, 1.0);
+            result[y * w + x] = to_u8(coverage * 255.0);
+        }
+    }
+
+    result
+}
+
+/// Second derivative of a 3×3 neighborhood along its isoline direction
+fn isoline_curvature(n: [[f32; 3]; 3]) -> f32 {
+    let gx = (n[1][2] - n[1][0]) * 0.5;
+    let gy = (n[2][1] - n[0][1]) * 0.5;
+    let g2 = gx * gx + gy * gy;
+    if g2 <= f32::EPSILON {
+        return 0.0;
+    }
+
+    let dxx = n[1][2] - 2.0 * n[1][1] + n[1][0];
+    let dyy = n[2][1] - 2.0 * n[1][1] + n[0][1];
+    let dxy = (n[2][2] - n[2][0] - n[0][2] + n[0][0]) * 0.25;
+
+    // Tangent t = (-gy, gx) / |g|: t_x² dxx + 2 t_x t_y dxy + t_y² dyy
+    ((gy * gy * dxx - 2.0 * gx * gy * dxy + gx * gx * dyy) / g2).abs()
+}
+
+/// Render a band running inward from the silhouette
+///
+/// Equivalent to the difference between the mask and the mask eroded by
+/// `inset_px`, but measured with the distance field rather than integer
+/// erosion, so fractional insets produce an anti-aliased inner border.
+///
+/// # Arguments
+///
+/// * `alpha_data` - Alpha channel as u8 array
+/// * `width` - Image width
+/// * `height` - Image height
+/// * `inset_px` - Band width in pixels, measured inward from the silhouette
+///
+/// # Returns
+///
+/// Band coverage per pixel; always 0 outside the silhouette
+#[wasm_bindgen]
+pub fn inset_outline(
+    alpha_data: &[u8],
+    width: u32,
+    height: u32,
+    inset_px: f32,
+) -> Vec<u8> {
+    let options = SdfOptions::default();
+    let distances = seed_distances(alpha_data, width as usize, height as usize, &options);
+
+    distances
+        .iter()
+        .zip(alpha_data)
+        .map(|(&d, &a)| {
+            if a < options.threshold {
+                return 0;
+            }
+
+            // Depth of the pixel center below the silhouette
+            let depth = d + 0.5;
+            let coverage = (inset_px - depth + 0.5).clamp(0.0, 1.0);
+            to_u8(coverage * 255.0)
+        })
+        .collect()
+}
+
+/// Estimate ambient occlusion from interior distance
+///
+/// Pixels deep inside the shape are treated as more occluded, giving a
+/// cheap self-shadowing term. Brightness falls off as
+/// `exp(-depth / radius)`, where `depth` is the distance inside the
+/// silhouette, so edges stay bright and the core darkens smoothly.
+///
+/// # Arguments
+///
+/// * `sdf` - Encoded SDF (128 = edge)
+/// * `width` - Image width
+/// * `height` - Image height
+/// * `radius` - Depth in pixels at which brightness drops to ~37%
+/// * `max_distance` - The `max_distance` the SDF was generated with
+///
+/// # Returns
+///
+/// Brightness per pixel: 255 at and outside the edge, darker inward
+#[wasm_bindgen]
+pub fn sdf_ao(
+    sdf: &[u8],
+    width: u32,
+    height: u32,
+    radius: f32,
+    max_distance: f32,
+) -> Vec<u8> {
+    let size = width as usize * height as usize;
+    let radius = radius.max(f32::EPSILON);
+
+    let mut result = vec![255u8; size];
+    for i in 0..size {
+        let depth = (-decode_distance(sdf[i], max_distance)).max(0.0);
+        result[i] = to_u8((-depth / radius).exp() * 255.0);
+    }
+
+    result
+}
+
+/// Map interior distance to a thickness mask for backlighting
+///
+/// Depth inside the silhouette is normalized by the deepest point of the
+/// SDF and raised to `curve`: 1 is linear, higher values concentrate
+/// brightness toward the core, and values below 1 fill the body out.
+///
+/// # Arguments
+///
+/// * `sdf` - Encoded SDF (128 = edge)
+/// * `width` - Image width
+/// * `height` - Image height
+/// * `max_distance` - The `max_distance` the SDF was generated with
+/// * `curve` - Falloff exponent applied to normalized depth
+///
+/// # Returns
+///
+/// Thickness per pixel: 0 at and outside the edge, 255 at the deepest
+/// interior point
+#[wasm_bindgen]
+pub fn sdf_thickness(
+    sdf: &[u8],
+    width: u32,
+    height: u32,
+    max_distance: f32,
+    curve: f32,
+) -> Vec<u8> {
+    let size = width as usize * height as usize;
+    let curve = curve.max(f32::EPSILON);
+
+    match normalized_depth(&sdf[..size], max_distance) {
+        Some(depth) => depth.iter().map(|&d| to_u8(d.powf(curve) * 255.0)).collect(),
+        None => vec![0u8; size],
+    }
+}
+
+/// Map interior distance to a depth map for parallax
+///
+/// The deepest interior point of the SDF sits on the near plane and depth
+/// recedes linearly toward the far plane at the silhouette edge; the edge
+/// itself and everything outside lie on the far plane. Planes are given
+/// as 0.0-1.0 and written as bytes, so `near = 1.0, far = 0.0` gives a
+/// white-is-near map and the reverse gives white-is-far.
+///
+/// # Arguments
+///
+/// * `sdf` - Encoded SDF (128 = edge)
+/// * `width` - Image width
+/// * `height` - Image height
+/// * `max_distance` - The `max_distance` the SDF was generated with
+/// * `near` - Depth of the sprite core (0.0-1.0)
+/// * `far` - Depth of the edge and background (0.0-1.0)
+///
+/// # Returns
+///
+/// Single-channel depth buffer
+#[wasm_bindgen]
+pub fn sdf_to_depth(
+    sdf: &[u8],
+    width: u32,
+    height: u32,
+    max_distance: f32,
+    near: f32,
+    far: f32,
+) -> Vec<u8> {
+    let size = width as usize * height as usize;
+
+    match normalized_depth(&sdf[..size], max_distance) {
+        Some(depth) => depth
+            .iter()
+            .map(|&d| to_u8((far + (near - far) * d) * 255.0))
+            .collect(),
+        None => vec![to_u8(far * 255.0); size],
+    }
+}
+
+/// Interior depth of each SDF texel, divided by the deepest one
+///
+/// 0.0 at and outside the edge, 1.0 at the deepest interior point; `None`
+/// if nothing lies inside the silhouette.
+fn normalized_depth(sdf: &[u8], max_distance: f32) -> Option<Vec<f32>> {
+    let depth: Vec<f32> = sdf
+        .iter()
+        .map(|&v| (-decode_distance(v, max_distance)).max(0.0))
+        .collect();
+    let deepest = depth.iter().copied().fold(0.0f32, f32::max);
+    if deepest <= 0.0 {
+        return None;
+    }
+
+    Some(depth.iter().map(|&d| d / deepest).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::disc;
+    use crate::sdf::generate_sdf;
+
+    #[test]
+    fn test_stroke_band() {
+        // Left half opaque: silhouette runs between x = 15 and x = 16
+        let size = 32;
+        let alpha: Vec<u8> = (0..size * size)
+            .map(|i| if i % size < 16 { 255 } else { 0 })
+            .collect();
+        let sdf = generate_sdf(&alpha, size as u32, size as u32, 16.0);
+        let stroke = sdf_stroke(&sdf, size as u32, size as u32, 4.0, 16.0);
+
+        let row = &stroke[8 * size..9 * size];
+        let total: f32 = row.iter().map(|&v| v as f32 / 255.0).sum();
+        let centre: f32 = row
+            .iter()
+            .enumerate()
+            .map(|(x, &v)| x as f32 * v as f32 / 255.0)
+            .sum::<f32>()
+            / total;
+
+        assert!((total - 4.0).abs() < 0.25, "stroke width {}", total);
+        assert!((centre - 15.5).abs() < 0.25, "stroke centre {}", centre);
+
+        // Deep interior and far exterior are untouched
+        assert_eq!(row[8], 0);
+        assert_eq!(row[24], 0);
+    }
+
+    #[test]
+    fn test_isolines_ramp() {
+        // Left half opaque: distance grows linearly from the edge at x = 16
+        let size = 32;
+        let alpha: Vec<u8> = (0..size * size)
+            .map(|i| if i % size < 16 { 255 } else { 0 })
+            .collect();
+        let sdf = generate_sdf(&alpha, size as u32, size as u32, 16.0);
+        let lines = sdf_isolines(&sdf, size as u32, size as u32, 4.0, 16.0);
+        let row = &lines[8 * size..9 * size];
+
+        // Contours at 0, 4 and 8px straddle pixel pairs on either side
+        for contour in [0usize, 4, 8] {
+            let (before, after) = (16 + contour - 1, 16 + contour);
+            assert!(row[before] > 64, "contour {}: {:?}", contour, row);
+            assert!(row[after] > 64, "contour {}: {:?}", contour, row);
+            assert!(row[15 - contour] > 64, "inner contour {}", contour);
+        }
+
+        // Nothing midway between contours
+        for x in [18, 22, 13, 9] {
+            assert_eq!(row[x], 0, "x = {}", x);
+        }
+    }
+
+    #[test]
+    fn test_adaptive_stroke_widens_corners() {
+        // 16x16 square at (16, 16) in a 48x48 frame
+        let size = 48usize;
+        let alpha: Vec<u8> = (0..size * size)
+            .map(|i| {
+                let (x, y) = (i % size, i / size);
+                if (16..32).contains(&x) && (16..32).contains(&y) { 255 } else { 0 }
+            })
+            .collect();
+        let sdf = generate_sdf(&alpha, size as u32, size as u32, 16.0);
+
+        let plain = sdf_stroke(&sdf, size as u32, size as u32, 4.0, 16.0);
+        let adaptive = sdf_stroke_adaptive(&sdf, size as u32, size as u32, 4.0, 16.0, 1.0);
+
+        // Total coverage in a 7x7 window around a point
+        let window = |mask: &[u8], cx: usize, cy: usize| -> f32 {
+            (cy - 3..=cy + 3)
+                .flat_map(|y| (cx - 3..=cx + 3).map(move |x| (x, y)))
+                .map(|(x, y)| mask[y * size + x] as f32 / 255.0)
+                .sum()
+        };
+
+        let corner_gain = window(&adaptive, 16, 16) / window(&plain, 16, 16);
+        let edge_gain = window(&adaptive, 16, 24) / window(&plain, 16, 24);
+        assert!(corner_gain > edge_gain + 0.1);
+        assert!((edge_gain - 1.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_inset_outline_band() {
+        // 24x24 square at (8, 8) in a 40x40 frame
+        let size = 40usize;
+        let alpha: Vec<u8> = (0..size * size)
+            .map(|i| {
+                let (x, y) = (i % size, i / size);
+                if (8..32).contains(&x) && (8..32).contains(&y) { 255 } else { 0 }
+            })
+            .collect();
+        let band = inset_outline(&alpha, size as u32, size as u32, 2.5);
+
+        // Entirely inside the original silhouette
+        for i in 0..size * size {
+            if alpha[i] == 0 {
+                assert_eq!(band[i], 0, "pixel {}", i);
+            }
+        }
+
+        // Across the middle row, 2.5px of band on each side of the square
+        let row = &band[20 * size..21 * size];
+        let left: f32 = row[..20].iter().map(|&v| v as f32 / 255.0).sum();
+        assert!((left - 2.5).abs() < 0.05, "band width {}", left);
+        assert_eq!(row[8..11], [255, 255, 128]);
+        assert_eq!(row[20], 0);
+    }
+
+    #[test]
+    fn test_ao_darkens_core() {
+        let size = 32usize;
+        let alpha = disc((size, size), (16.0, 16.0), 10.0);
+        let sdf = generate_sdf(&alpha, size as u32, size as u32, 16.0);
+        let ao = sdf_ao(&sdf, size as u32, size as u32, 4.0, 16.0);
+
+        // Walk the center row from the rim (x = 6) to the center (x = 16)
+        let row = &ao[16 * size..17 * size];
+        for x in 6..16 {
+            assert!(row[x + 1] <= row[x], "not monotonic at x = {}", x);
+        }
+        assert!(row[16] < row[6]);
+        assert_eq!(row[6], 255);
+
+        // Background is unoccluded
+        assert_eq!(ao[0], 255);
+    }
+
+    #[test]
+    fn test_thickness_curve() {
+        let size = 32usize;
+        let alpha = disc((size, size), (16.0, 16.0), 12.0);
+        let sdf = generate_sdf(&alpha, size as u32, size as u32, 16.0);
+
+        let linear = sdf_thickness(&sdf, size as u32, size as u32, 16.0, 1.0);
+        let tight = sdf_thickness(&sdf, size as u32, size as u32, 16.0, 3.0);
+
+        // Both peak at the center and vanish at the rim and outside
+        let center = 16 * size + 16;
+        assert_eq!(linear[center], 255);
+        assert_eq!(tight[center], 255);
+        assert_eq!(linear[16 * size + 4], 0);
+        assert_eq!(tight[0], 0);
+
+        // A higher curve keeps less of the body bright
+        let bright = |mask: &[u8]| mask.iter().filter(|&&v| v >= 128).count();
+        assert!(bright(&tight) < bright(&linear));
+        for i in 0..size * size {
+            assert!(tight[i] <= linear[i], "pixel {}", i);
+        }
+    }
+
+    #[test]
+    fn test_dashed_outline() {
+        // 40x40 square at (12, 12): the inner contour starts at its top-left
+        // pixel and runs right along row 12
+        let size = 64usize;
+        let alpha: Vec<u8> = (0..size * size)
+            .map(|i| {
+                let (x, y) = (i % size, i / size);
+                if (12..52).contains(&x) && (12..52).contains(&y) { 255 } else { 0 }
+            })
+            .collect();
+        let sdf = generate_sdf(&alpha, size as u32, size as u32, 16.0);
+        let dashes = |phase: f32| dashed_outline(&sdf, size as u32, size as u32, 4.0, phase, 2.0, 16.0);
+
+        let still = dashes(0.0);
+        let row = &still[12 * size..13 * size];
+
+        // Alternating 4px dashes and gaps along the top edge
+        for x in 12..48 {
+            let on = (x - 12) % 8 < 4;
+            assert_eq!(row[x] == 255, on, "x = {}: {:?}", x, &row[12..48]);
+        }
+
+        // Advancing the phase by 2px shifts the pattern back by 2px
+        let moved = dashes(2.0);
+        for x in 12..46 {
+            assert_eq!(moved[12 * size + x], still[12 * size + x + 2], "x = {}", x);
+        }
+
+        // Nothing off the band
+        assert_eq!(still[32 * size + 32], 0);
+        assert_eq!(still[0], 0);
+    }
+
+    #[test]
+    fn test_depth_from_disc() {
+        let size = 32usize;
+        let alpha = disc((size, size), (16.0, 16.0), 12.0);
+        let sdf = generate_sdf(&alpha, size as u32, size as u32, 16.0);
+
+        // Near is dark, far is light
+        let depth = sdf_to_depth(&sdf, size as u32, size as u32, 16.0, 0.1, 0.9);
+        let center = 16 * size + 16;
+        let far = to_u8(0.9 * 255.0);
+        assert_eq!(depth[center], to_u8(0.1 * 255.0));
+        assert_eq!(depth.iter().min(), Some(&depth[center]));
+        assert_eq!(depth[0], far);
+        assert_eq!(depth[16 * size + 30], far);
+
+        // Receding monotonically from the core out to the rim
+        let row = &depth[16 * size + 16..16 * size + 30];
+        assert!(row.windows(2).all(|pair| pair[0] <= pair[1]), "{:?}", row);
+
+        // No interior at all: everything on the far plane
+        let empty = generate_sdf(&vec![0; size * size], size as u32, size as u32, 16.0);
+        assert!(sdf_to_depth(&empty, size as u32, size as u32, 16.0, 0.1, 0.9)
+            .iter()
+            .all(|&v| v == far));
+    }
+}
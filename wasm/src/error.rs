@@ -0,0 +1,110 @@
+//! Error Handling
+//!
+//! Errors reported by functions that validate their input. They become a
+//! JS `Error` when crossing the WASM boundary.
+
+use std::fmt;
+
+use wasm_bindgen::prelude::*;
+
+/// Error returned by validating entry points
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// A buffer's length doesn't match what the image dimensions require
+    LengthMismatch {
+        buffer: &'static str,
+        expected: usize,
+        actual: usize,
+    },
+    /// An argument is outside the range the function supports
+    InvalidArgument {
+        name: &'static str,
+        reason: &'static str,
+    },
+    /// Encoded data is truncated or otherwise malformed
+    InvalidEncoding { reason: &'static str },
+    /// A region extends past the image it was taken from
+    RegionOutOfBounds {
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    },
+    /// An internal panic caught by a `safe` feature entry point
+    Panic { message: String },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::LengthMismatch {
+                buffer,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "`{}` has length {}, expected {}",
+                buffer, actual, expected
+            ),
+            Error::InvalidArgument { name, reason } => write!(f, "`{}` {}", name, reason),
+            Error::InvalidEncoding { reason } => write!(f, "invalid encoding: {}", reason),
+            Error::RegionOutOfBounds {
+                x,
+                y,
+                width,
+                height,
+            } => write!(
+                f,
+                "region {}x{} at ({}, {}) is outside the image",
+                width, height, x, y
+            ),
+            Error::Panic { message } => write!(f, "internal panic: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<Error> for JsValue {
+    fn from(err: Error) -> JsValue {
+        js_sys::Error::new(&err.to_string()).into()
+    }
+}
+
+/// Check that `buffer` holds exactly `expected` elements
+pub(crate) fn check_len(buffer: &'static str, actual: usize, expected: usize) -> Result<(), Error> {
+    if actual == expected {
+        Ok(())
+    } else {
+        Err(Error::LengthMismatch {
+            buffer,
+            expected,
+            actual,
+        })
+    }
+}
+
+/// Check that a `rw`×`rh` region at (`rx`, `ry`) lies inside a
+/// `width`×`height` image
+pub(crate) fn check_region(
+    rx: u32,
+    ry: u32,
+    rw: u32,
+    rh: u32,
+    width: u32,
+    height: u32,
+) -> Result<(), Error> {
+    let fits_x = rx.checked_add(rw).is_some_and(|end| end <= width);
+    let fits_y = ry.checked_add(rh).is_some_and(|end| end <= height);
+
+    if fits_x && fits_y {
+        Ok(())
+    } else {
+        Err(Error::RegionOutOfBounds {
+            x: rx,
+            y: ry,
+            width: rw,
+            height: rh,
+        })
+    }
+}
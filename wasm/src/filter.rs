@@ -0,0 +1,424 @@
+//! Image Filters
+//!
+//! General-purpose convolution filters for RGBA frames, such as the
+//! background defocus, glow and upscale sharpening in the virtual frame.
+//!
+//! RGBA filters treat the input as straight (non-premultiplied) alpha and
+//! convolve in premultiplied space, so transparent pixels never bleed their
+//! RGB into visible neighbors as dark fringes.
+
+use wasm_bindgen::prelude::*;
+
+use crate::color::to_u8;
+
+/// Apply a separable Gaussian blur to all four RGBA channels
+///
+/// # Arguments
+///
+/// * `image_data` - RGBA pixel data (straight alpha)
+/// * `width` - Image width
+/// * `height` - Image height
+/// * `sigma` - Standard deviation of the Gaussian in pixels
+///
+/// # Kernel
+///
+/// The kernel radius is `ceil(3 * sigma)`, which covers 99.7% of the
+/// Gaussian's mass; taps beyond that contribute less than 0.5/255 to any
+/// channel. Weights are normalized over the taps that fall inside the image,
+/// so edges are neither darkened nor brightened.
+///
+/// # Returns
+///
+/// Blurred RGBA pixel data (straight alpha). A `sigma` of zero or less
+/// returns the input unchanged.
+#[wasm_bindgen]
+pub fn gaussian_blur_rgba(
+    image_data: &[u8],
+    width: u32,
+    height: u32,
+    sigma: f32,
+) -> Vec<u8> {
+    let w = width as usize;
+    let h = height as usize;
+
+    if sigma <= 0.0 {
+        return image_data.to_vec();
+    }
+
+    let kernel = gaussian_kernel(sigma);
+
+    // Premultiply so color is weighted by coverage
+    let premul = premultiplied(image_data, w * h);
+
+    let temp = convolve_rgba(&premul, w, h, &kernel, true);
+    let blurred = convolve_rgba(&temp, w, h, &kernel, false);
+
+    // Unpremultiply back to straight alpha
+    let mut result = vec![0u8; w * h * 4];
+    for i in 0..(w * h) {
+        let a = blurred[i * 4 + 3];
+        if a > 0.0 {
+            let scale = 255.0 / a;
+            result[i * 4] = to_u8(blurred[i * 4] * scale);
+            result[i * 4 + 1] = to_u8(blurred[i * 4 + 1] * scale);
+            result[i * 4 + 2] = to_u8(blurred[i * 4 + 2] * scale);
+        }
+        result[i * 4 + 3] = to_u8(a);
+    }
+
+    result
+}
+
+/// Sharpen an image by adding back its high-pass detail
+///
+/// Classic unsharp mask: the image is blurred with the same separable
+/// Gaussian as [`gaussian_blur_rgba`], the blur is subtracted to isolate
+/// detail, and `amount` of that detail is added back. Color is sharpened in
+/// premultiplied space so transparent neighbors never darken the sprite's
+/// edge; alpha is left untouched so the silhouette does not move.
+///
+/// # Arguments
+///
+/// * `image_data` - RGBA pixel data (straight alpha)
+/// * `width` - Image width
+/// * `height` - Image height
+/// * `amount` - Detail gain; 0.5-1.5 is typical for upscaled sprites
+/// * `radius` - Gaussian sigma in pixels selecting the detail scale
+///
+/// # Returns
+///
+/// Sharpened RGBA pixel data (straight alpha). Flat regions are unchanged,
+/// as is everything when `amount` or `radius` is zero or less.
+#[wasm_bindgen]
+pub fn unsharp_mask(
+    image_data: &[u8],
+    width: u32,
+    height: u32,
+    amount: f32,
+    radius: f32,
+) -> Vec<u8> {
+    let w = width as usize;
+    let h = height as usize;
+
+    if amount <= 0.0 || radius <= 0.0 {
+        return image_data.to_vec();
+    }
+
+    let kernel = gaussian_kernel(radius);
+    let premul = premultiplied(image_data, w * h);
+    let temp = convolve_rgba(&premul, w, h, &kernel, true);
+    let blurred = convolve_rgba(&temp, w, h, &kernel, false);
+
+    let mut result = image_data.to_vec();
+    for i in 0..(w * h) {
+        let a = premul[i * 4 + 3];
+        if a <= 0.0 {
+            continue;
+        }
+
+        let scale = 255.0 / a;
+        for c in 0..3 {
+            let idx = i * 4 + c;
+            let detail = premul[idx] - blurred[idx];
+            let sharpened = (premul[idx] + amount * detail).clamp(0.0, a);
+            result[idx] = to_u8(sharpened * scale);
+        }
+    }
+
+    result
+}
+
+/// Extract bright pixels and blur them into a single-channel bloom mask
+///
+/// # Arguments
+///
+/// * `image_data` - RGBA pixel data (straight alpha)
+/// * `width` - Image width
+/// * `height` - Image height
+/// * `luminance_threshold` - Minimum Rec. 709 luminance (0.0-1.0) to bloom
+/// * `blur_radius` - Box blur radius in pixels
+///
+/// # Returns
+///
+/// Bloom intensity per pixel. Bright pixels contribute their luminance
+/// (weighted by alpha, so transparent pixels never glow); everything below
+/// the threshold contributes nothing.
+#[wasm_bindgen]
+pub fn bloom_mask(
+    image_data: &[u8],
+    width: u32,
+    height: u32,
+    luminance_threshold: f32,
+    blur_radius: u32,
+) -> Vec<u8> {
+    let w = width as usize;
+    let h = height as usize;
+
+    let mut bright = vec![0u8; w * h];
+    for i in 0..(w * h) {
+        let px = &image_data[i * 4..i * 4 + 4];
+        let luma = (0.2126 * px[0] as f32 + 0.7152 * px[1] as f32 + 0.0722 * px[2] as f32)
+            / 255.0
+            * (px[3] as f32 / 255.0);
+
+        if luma >= luminance_threshold {
+            bright[i] = to_u8(luma * 255.0);
+        }
+    }
+
+    if blur_radius == 0 {
+        return bright;
+    }
+
+    box_blur(&bright, w, h, blur_radius as usize)
+}
+
+/// Separable box blur of a single-channel buffer
+///
+/// Samples past the border, on every side, are clamped to the edge pixel,
+/// so each output averages exactly `2 * radius + 1` taps per axis. Means
+/// are rounded half-up like [`to_u8`] rather than truncated.
+pub(crate) fn box_blur(alpha: &[u8], w: usize, h: usize, radius: usize) -> Vec<u8> {
+    let taps = (radius * 2 + 1) as u32;
+
+    // Horizontal pass
+    let mut temp = vec![0u8; w * h];
+    for y in 0..h {
+        for x in 0..w {
+            let mut sum = 0u32;
+            for dx in 0..=radius * 2 {
+                let sx = (x + dx).saturating_sub(radius).min(w - 1);
+                sum += alpha[y * w + sx] as u32;
+            }
+
+            temp[y * w + x] = ((sum + taps / 2) / taps) as u8;
+        }
+    }
+
+    // Vertical pass
+    let mut result = vec![0u8; w * h];
+    for y in 0..h {
+        for x in 0..w {
+            let mut sum = 0u32;
+            for dy in 0..=radius * 2 {
+                let sy = (y + dy).saturating_sub(radius).min(h - 1);
+                sum += temp[sy * w + x] as u32;
+            }
+
+            result[y * w + x] = ((sum + taps / 2) / taps) as u8;
+        }
+    }
+
+    result
+}
+
+/// Mean of each pixel's `(2 * radius + 1)²` window over a float buffer
+///
+/// Uses a summed-area table, so cost is independent of `radius`. Windows
+/// are cropped at the border and averaged over the pixels they cover.
+pub(crate) fn box_mean(data: &[f32], w: usize, h: usize, radius: usize) -> Vec<f32> {
+    // Summed-area table with a zero top row and left column
+    let stride = w + 1;
+    let mut table = vec![0.0f64; stride * (h + 1)];
+    for y in 0..h {
+        let mut row = 0.0f64;
+        for x in 0..w {
+            row += data[y * w + x] as f64;
+            table[(y + 1) * stride + x + 1] = table[y * stride + x + 1] + row;
+        }
+    }
+
+    let mut result = vec![0.0f32; w * h];
+    for y in 0..h {
+        let (y0, y1) = (y.saturating_sub(radius), (y + radius + 1).min(h));
+        for x in 0..w {
+            let (x0, x1) = (x.saturating_sub(radius), (x + radius + 1).min(w));
+            let sum = table[y1 * stride + x1] - table[y0 * stride + x1] - table[y1 * stride + x0]
+                + table[y0 * stride + x0];
+            result[y * w + x] = (sum / ((x1 - x0) * (y1 - y0)) as f64) as f32;
+        }
+    }
+
+    result
+}
+
+/// Convert straight-alpha RGBA bytes to premultiplied floats (0-255)
+fn premultiplied(image_data: &[u8], pixels: usize) -> Vec<f32> {
+    let mut premul = vec![0.0f32; pixels * 4];
+    for i in 0..pixels {
+        let a = image_data[i * 4 + 3] as f32 / 255.0;
+        premul[i * 4] = image_data[i * 4] as f32 * a;
+        premul[i * 4 + 1] = image_data[i * 4 + 1] as f32 * a;
+        premul[i * 4 + 2] = image_data[i * 4 + 2] as f32 * a;
+        premul[i * 4 + 3] = image_data[i * 4 + 3] as f32;
+    }
+    premul
+}
+
+/// Build a 1D Gaussian kernel of radius `ceil(3 * sigma)`
+fn gaussian_kernel(sigma: f32) -> Vec<f32> {
+    let radius = (sigma * 3.0).ceil() as i32;
+    let denom = 2.0 * sigma * sigma;
+
+    (-radius..=radius)
+        .map(|i| (-((i * i) as f32) / denom).exp())
+        .collect()
+}
+
+/// Convolve interleaved RGBA floats with a 1D kernel along one axis
+fn convolve_rgba(
+    data: &[f32],
+    w: usize,
+    h: usize,
+    kernel: &[f32],
+    horizontal: bool,
+) -> Vec<f32> {
+    let radius = (kernel.len() / 2) as i32;
+    let mut result = vec![0.0f32; w * h * 4];
+
+    for y in 0..h {
+        for x in 0..w {
+            let mut sum = [0.0f32; 4];
+            let mut weight = 0.0f32;
+
+            for (k, kw) in kernel.iter().enumerate() {
+                let offset = k as i32 - radius;
+                let (sx, sy) = if horizontal {
+                    (x as i32 + offset, y as i32)
+                } else {
+                    (x as i32, y as i32 + offset)
+                };
+
+                if sx >= 0 && sx < w as i32 && sy >= 0 && sy < h as i32 {
+                    let src = ((sy as usize) * w + sx as usize) * 4;
+                    for c in 0..4 {
+                        sum[c] += data[src + c] * kw;
+                    }
+                    weight += kw;
+                }
+            }
+
+            let dst = (y * w + x) * 4;
+            for c in 0..4 {
+                result[dst + c] = sum[c] / weight;
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gaussian_delta() {
+        // Single opaque white pixel in the middle of a 9x9 transparent field
+        let (w, h) = (9usize, 9usize);
+        let mut data = vec![0u8; w * h * 4];
+        let center = (4 * w + 4) * 4;
+        data[center..center + 4].copy_from_slice(&[255, 255, 255, 255]);
+
+        let result = gaussian_blur_rgba(&data, w as u32, h as u32, 1.0);
+        let alpha = |x: usize, y: usize| result[(y * w + x) * 4 + 3];
+
+        // Peak at the center, falling off symmetrically
+        assert!(alpha(4, 4) > alpha(3, 4));
+        assert!(alpha(3, 4) > alpha(2, 4));
+        assert_eq!(alpha(3, 4), alpha(5, 4));
+        assert_eq!(alpha(4, 3), alpha(4, 5));
+        assert_eq!(alpha(3, 4), alpha(4, 3));
+        assert_eq!(alpha(3, 3), alpha(5, 5));
+
+        // Energy is approximately preserved
+        let total: u32 = (0..w * h).map(|i| result[i * 4 + 3] as u32).sum();
+        assert!((total as i32 - 255).abs() <= 12, "total alpha {}", total);
+
+        // Color stays white where there is coverage (no dark fringe)
+        assert_eq!(result[(4 * w + 3) * 4], 255);
+    }
+
+    #[test]
+    fn test_unsharp_step_edge() {
+        // Opaque gray step: 64 for x < 8, 192 from x = 8
+        let (w, h) = (16usize, 4usize);
+        let mut data = vec![0u8; w * h * 4];
+        for (i, px) in data.chunks_exact_mut(4).enumerate() {
+            let v = if i % w < 8 { 64 } else { 192 };
+            px.copy_from_slice(&[v, v, v, 255]);
+        }
+
+        let result = unsharp_mask(&data, w as u32, h as u32, 1.0, 1.0);
+        let gray = |x: usize| result[(w + x) * 4];
+
+        // Overshoot on both sides steepens the edge
+        assert!(gray(7) < 64);
+        assert!(gray(8) > 192);
+        assert!(gray(8) - gray(7) > 128);
+
+        // Flat regions beyond the kernel's reach are unchanged
+        assert_eq!(gray(0), 64);
+        assert_eq!(gray(15), 192);
+        assert!(result.chunks_exact(4).all(|px| px[3] == 255));
+    }
+
+    #[test]
+    fn test_bloom_single_bright_pixel() {
+        let (w, h) = (9usize, 9usize);
+        let mut data = vec![0u8; w * h * 4];
+        for px in data.chunks_exact_mut(4) {
+            px.copy_from_slice(&[40, 40, 40, 255]);
+        }
+        let center = (4 * w + 4) * 4;
+        data[center..center + 4].copy_from_slice(&[255, 255, 255, 255]);
+
+        let bloom = bloom_mask(&data, w as u32, h as u32, 0.5, 1);
+
+        // 3x3 blob centered on the bright pixel
+        for y in 0..h {
+            for x in 0..w {
+                let inside = x.abs_diff(4) <= 1 && y.abs_diff(4) <= 1;
+                let expected = if inside { 255 / 9 } else { 0 };
+                assert_eq!(bloom[y * w + x], expected, "pixel ({}, {})", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn test_box_blur_border_symmetric() {
+        // An edge at the left border and its mirror at the right border
+        // blur to mirror images of each other
+        let (w, h) = (9usize, 3usize);
+        let left: Vec<u8> = (0..w * h).map(|i| if i % w < 2 { 255 } else { 0 }).collect();
+        let right: Vec<u8> = (0..w * h).map(|i| if i % w >= w - 2 { 255 } else { 0 }).collect();
+
+        let a = box_blur(&left, w, h, 2);
+        let b = box_blur(&right, w, h, 2);
+        for y in 0..h {
+            for x in 0..w {
+                assert_eq!(a[y * w + x], b[y * w + w - 1 - x], "({}, {})", x, y);
+            }
+        }
+
+        // Clamped taps keep a flat image flat right up to every edge
+        assert!(box_blur(&[200; 20], 5, 4, 3).iter().all(|&v| v == 200));
+    }
+
+    #[test]
+    fn test_box_blur_rounds_to_nearest() {
+        // An odd tap count never averages to exactly .5, so rounding
+        // half-up means rounding to nearest: 2/3 becomes 1 (truncation
+        // gave 0) and 4/3 stays 1
+        assert_eq!(box_blur(&[0, 0, 2, 2], 4, 1, 1), [0, 1, 1, 2]);
+        assert_eq!(box_blur(&[0, 2, 2, 2], 4, 1, 1), [1, 1, 2, 2]);
+
+        // Both passes round: 3 → 1 across a row, then 1/3 down a column
+        let mut data = [0u8; 9];
+        data[4] = 3;
+        let blurred = box_blur(&data, 3, 3, 1);
+        assert_eq!(blurred[4], 0);
+        data[4] = 5;
+        assert_eq!(box_blur(&data, 3, 3, 1)[4], 1);
+    }
+}
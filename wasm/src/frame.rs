@@ -0,0 +1,296 @@
+//! Frame Input
+//!
+//! A validated RGBA frame, mirroring the browser's `ImageData`, so pixel
+//! data and dimensions travel together and can't get out of sync, plus
+//! primitives for allocating and clearing raw RGBA buffers.
+
+use wasm_bindgen::prelude::*;
+
+use crate::error::{check_len, Error};
+use crate::mipmap::{extract_region, generate_mipmaps, MipmapLevel};
+use crate::normalize::{calculate_centroid, Centroid};
+use crate::sdf::generate_sdf;
+
+/// RGBA pixel data with its dimensions
+#[wasm_bindgen]
+pub struct Frame {
+    data: Vec<u8>,
+    width: u32,
+    height: u32,
+}
+
+#[wasm_bindgen]
+impl Frame {
+    /// Wrap RGBA pixel data (e.g. `ImageData.data`)
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::LengthMismatch`] if `data` isn't
+    /// `width * height * 4` bytes.
+    #[wasm_bindgen(constructor)]
+    pub fn new(data: Vec<u8>, width: u32, height: u32) -> Result<Frame, Error> {
+        check_len("data", data.len(), width as usize * height as usize * 4)?;
+        Ok(Frame { data, width, height })
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn data(&self) -> Vec<u8> {
+        self.data.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+}
+
+impl Frame {
+    /// The alpha channel, one byte per pixel
+    fn alpha(&self) -> Vec<u8> {
+        self.data.chunks_exact(4).map(|px| px[3]).collect()
+    }
+}
+
+/// [`generate_sdf`] on a frame's alpha channel
+#[wasm_bindgen]
+pub fn generate_sdf_frame(frame: &Frame, max_distance: f32) -> Vec<u8> {
+    generate_sdf(&frame.alpha(), frame.width, frame.height, max_distance)
+}
+
+/// [`generate_mipmaps`] of a frame
+#[wasm_bindgen]
+pub fn generate_mipmaps_frame(frame: &Frame, levels: u32) -> Vec<MipmapLevel> {
+    generate_mipmaps(&frame.data, frame.width, frame.height, levels)
+}
+
+/// [`calculate_centroid`] of a frame's alpha channel
+#[wasm_bindgen]
+pub fn calculate_centroid_frame(frame: &Frame, threshold: u8) -> Centroid {
+    calculate_centroid(&frame.alpha(), frame.width, frame.height, threshold)
+}
+
+/// [`generate_sdf`] on any one channel of RGBA data
+///
+/// For buffers that carry the mask somewhere other than alpha, such as a
+/// single-channel matte uploaded as the red channel of an RGBA texture;
+/// reads every fourth byte starting at `channel` without a JS-side repack.
+///
+/// # Arguments
+///
+/// * `image_data` - RGBA pixel data
+/// * `width` - Image width
+/// * `height` - Image height
+/// * `channel` - Channel holding the mask: 0 = R, 1 = G, 2 = B, 3 = A
+/// * `max_distance` - Maximum distance to compute
+///
+/// # Errors
+///
+/// Returns [`Error::LengthMismatch`] if `image_data` isn't
+/// `width * height * 4` bytes, or [`Error::InvalidArgument`] if `channel`
+/// is above 3.
+#[wasm_bindgen]
+pub fn generate_sdf_channel(
+    image_data: &[u8],
+    width: u32,
+    height: u32,
+    channel: u8,
+    max_distance: f32,
+) -> Result<Vec<u8>, Error> {
+    let mask = extract_channel(image_data, width, height, channel)?;
+    Ok(generate_sdf(&mask, width, height, max_distance))
+}
+
+/// [`calculate_centroid`] of any one channel of RGBA data
+///
+/// Channel-selecting counterpart of [`calculate_centroid_frame`]; see
+/// [`generate_sdf_channel`] for the arguments and errors.
+#[wasm_bindgen]
+pub fn calculate_centroid_channel(
+    image_data: &[u8],
+    width: u32,
+    height: u32,
+    channel: u8,
+    threshold: u8,
+) -> Result<Centroid, Error> {
+    let mask = extract_channel(image_data, width, height, channel)?;
+    Ok(calculate_centroid(&mask, width, height, threshold))
+}
+
+/// Copy one channel of validated RGBA data out, one byte per pixel
+fn extract_channel(
+    image_data: &[u8],
+    width: u32,
+    height: u32,
+    channel: u8,
+) -> Result<Vec<u8>, Error> {
+    check_len("image_data", image_data.len(), width as usize * height as usize * 4)?;
+    if channel > 3 {
+        return Err(Error::InvalidArgument {
+            name: "channel",
+            reason: "must be 0-3 (R, G, B or A)",
+        });
+    }
+
+    Ok(image_data
+        .chunks_exact(4)
+        .map(|px| px[channel as usize])
+        .collect())
+}
+
+/// Allocate an RGBA buffer filled with one color
+///
+/// # Arguments
+///
+/// * `width` - Image width
+/// * `height` - Image height
+/// * `color` - Packed `0xRRGGBBAA`, as in CSS hex notation
+#[wasm_bindgen]
+pub fn fill_rgba(width: u32, height: u32, color: u32) -> Vec<u8> {
+    color.to_be_bytes().repeat(width as usize * height as usize)
+}
+
+/// Zero a buffer in place (transparent black for RGBA)
+#[wasm_bindgen]
+pub fn clear_rgba(data: &mut [u8]) {
+    data.fill(0);
+}
+
+/// Split a grid spritesheet into one frame per cell
+///
+/// # Arguments
+///
+/// * `image_data` - RGBA pixel data of the whole sheet
+/// * `width` - Sheet width
+/// * `height` - Sheet height
+/// * `cols` - Number of cells across
+/// * `rows` - Number of cells down
+///
+/// # Returns
+///
+/// `cols * rows` frames of `width / cols` × `height / rows` pixels, in
+/// row-major order (left to right, then top to bottom).
+///
+/// # Errors
+///
+/// Returns [`Error::LengthMismatch`] if `image_data` isn't
+/// `width * height * 4` bytes, and [`Error::InvalidArgument`] if `cols` or
+/// `rows` is zero or doesn't divide the sheet evenly.
+#[wasm_bindgen]
+pub fn slice_grid(
+    image_data: &[u8],
+    width: u32,
+    height: u32,
+    cols: u32,
+    rows: u32,
+) -> Result<Vec<Frame>, Error> {
+    check_len("image_data", image_data.len(), width as usize * height as usize * 4)?;
+    if cols == 0 || !width.is_multiple_of(cols) {
+        return Err(Error::InvalidArgument {
+            name: "cols",
+            reason: "must evenly divide the sheet width",
+        });
+    }
+    if rows == 0 || !height.is_multiple_of(rows) {
+        return Err(Error::InvalidArgument {
+            name: "rows",
+            reason: "must evenly divide the sheet height",
+        });
+    }
+
+    let cell_w = width / cols;
+    let cell_h = height / rows;
+    let mut frames = Vec::with_capacity((cols * rows) as usize);
+
+    for row in 0..rows {
+        for col in 0..cols {
+            let (x, y) = (col * cell_w, row * cell_h);
+            frames.push(Frame {
+                data: extract_region(image_data, width, 4, x, y, cell_w, cell_h),
+                width: cell_w,
+                height: cell_h,
+            });
+        }
+    }
+
+    Ok(frames)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_validation() {
+        assert!(Frame::new(vec![0; 4 * 3 * 4], 4, 3).is_ok());
+        assert!(Frame::new(vec![0; 4 * 3 * 4 - 1], 4, 3).is_err());
+        assert!(Frame::new(vec![0; 4 * 3], 4, 3).is_err());
+
+        // Frame entry points match the raw-buffer ones
+        let data: Vec<u8> = (0..8 * 8)
+            .flat_map(|i| [10, 20, 30, if i % 8 < 3 { 255 } else { 0 }])
+            .collect();
+        let alpha: Vec<u8> = data.chunks_exact(4).map(|px| px[3]).collect();
+        let frame = Frame::new(data, 8, 8).unwrap();
+        assert_eq!(generate_sdf_frame(&frame, 8.0), generate_sdf(&alpha, 8, 8, 8.0));
+        assert_eq!(calculate_centroid_frame(&frame, 128).area, 24);
+    }
+
+    #[test]
+    fn test_slice_grid() {
+        // 4x4 sheet where every pixel's red channel is its index
+        let data: Vec<u8> = (0..16u8).flat_map(|i| [i, 0, 0, 255]).collect();
+
+        let cells = slice_grid(&data, 4, 4, 2, 2).unwrap();
+        assert_eq!(cells.len(), 4);
+
+        let red = |f: &Frame| f.data().chunks_exact(4).map(|px| px[0]).collect::<Vec<u8>>();
+        assert!(cells.iter().all(|f| f.width() == 2 && f.height() == 2));
+        assert_eq!(red(&cells[0]), [0, 1, 4, 5]);
+        assert_eq!(red(&cells[1]), [2, 3, 6, 7]);
+        assert_eq!(red(&cells[2]), [8, 9, 12, 13]);
+        assert_eq!(red(&cells[3]), [10, 11, 14, 15]);
+
+        assert!(slice_grid(&data, 4, 4, 3, 2).is_err());
+        assert!(slice_grid(&data, 4, 4, 2, 0).is_err());
+    }
+
+    #[test]
+    fn test_fill_and_clear() {
+        let mut data = fill_rgba(3, 2, 0x1020_30ff);
+        assert_eq!(data.len(), 3 * 2 * 4);
+        assert!(data.chunks_exact(4).all(|px| px == [0x10, 0x20, 0x30, 0xff]));
+        assert!(fill_rgba(0, 5, 0xffff_ffff).is_empty());
+
+        clear_rgba(&mut data);
+        assert!(data.iter().all(|&v| v == 0));
+    }
+
+    #[test]
+    fn test_mask_in_red_channel() {
+        // Same mask stored once in alpha and once in red
+        let mask: Vec<u8> = (0..12 * 10)
+            .map(|i| if (2..7).contains(&(i % 12)) && (3..9).contains(&(i / 12)) { 255 } else { 0 })
+            .collect();
+        let in_alpha: Vec<u8> = mask.iter().flat_map(|&m| [0, 0, 0, m]).collect();
+        let in_red: Vec<u8> = mask.iter().flat_map(|&m| [m, 90, 90, 255]).collect();
+
+        let red = calculate_centroid_channel(&in_red, 12, 10, 0, 128).unwrap();
+        let alpha = calculate_centroid_channel(&in_alpha, 12, 10, 3, 128).unwrap();
+        let direct = calculate_centroid(&mask, 12, 10, 128);
+        assert_eq!((red.x, red.y, red.area), (alpha.x, alpha.y, alpha.area));
+        assert_eq!((red.x, red.y, red.area), (direct.x, direct.y, direct.area));
+        assert_eq!(red.area, 30);
+
+        assert_eq!(
+            generate_sdf_channel(&in_red, 12, 10, 0, 8.0).unwrap(),
+            generate_sdf(&mask, 12, 10, 8.0)
+        );
+        assert!(calculate_centroid_channel(&in_red, 12, 10, 4, 128).is_err());
+        assert!(generate_sdf_channel(&in_red[4..], 12, 10, 0, 8.0).is_err());
+    }
+}
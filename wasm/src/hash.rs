@@ -0,0 +1,40 @@
+//! Buffer Hashing
+//!
+//! Fast, stable fingerprints of input mattes and generated outputs, used to
+//! key caches of SDFs and mipmaps.
+
+use wasm_bindgen::prelude::*;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Hash a byte buffer with 64-bit FNV-1a
+///
+/// Non-cryptographic and deterministic: the result depends only on the
+/// bytes, never on platform, endianness or build, so hashes can be shared
+/// between clients. Returned to JS as a `BigInt`.
+#[wasm_bindgen]
+pub fn hash_buffer(data: &[u8]) -> u64 {
+    data.iter().fold(FNV_OFFSET_BASIS, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_buffer() {
+        // Published FNV-1a test vectors
+        assert_eq!(hash_buffer(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(hash_buffer(b"a"), 0xaf63_dc4c_8601_ec8c);
+
+        let data: Vec<u8> = (0..1024).map(|i| (i * 7 % 256) as u8).collect();
+        assert_eq!(hash_buffer(&data), hash_buffer(&data.clone()));
+
+        let mut changed = data.clone();
+        changed[512] ^= 1;
+        assert_ne!(hash_buffer(&data), hash_buffer(&changed));
+    }
+}
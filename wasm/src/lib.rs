@@ -9,6 +9,16 @@
 //! - `sdf`: Signed Distance Field generation for parallax effects
 //! - `mipmap`: Fast mipmap pyramid generation for zoom/pan
 //! - `normalize`: Alpha matte normalization and centering
+//! - `filter`: General-purpose image filters (blur, bloom, sharpen)
+//! - `effects`: Outline and glow masks derived from SDFs
+//! - `color`: sRGB/linear color space conversion
+//! - `error`: Error type for functions that validate their input
+//! - `mask`: Compact encodings and diffing for single-channel masks
+//! - `hash`: Stable buffer fingerprints for caching
+//! - `sample`: Bilinear texture lookups at arbitrary UVs
+//! - `frame`: Validated RGBA frame input
+//! - `composite`: Seamless (Poisson) pasting and crossfades between frames
+//! - `safe`: Panic-catching entry points (`safe` feature)
 //!
 //! # Usage
 //!
@@ -24,11 +34,41 @@ use wasm_bindgen::prelude::*;
 pub mod sdf;
 pub mod mipmap;
 pub mod normalize;
+pub mod filter;
+pub mod effects;
+pub mod color;
+pub mod error;
+pub mod mask;
+pub mod hash;
+pub mod sample;
+pub mod frame;
+pub mod composite;
+#[cfg(feature = "safe")]
+pub mod safe;
+#[cfg(test)]
+mod test_util;
 
 // Re-export main functions
 pub use sdf::generate_sdf;
 pub use mipmap::generate_mipmaps;
-pub use normalize::{normalize_matte, Centroid};
+pub use normalize::{normalize_matte, Bounds, Centroid};
+pub use error::Error;
+pub use frame::Frame;
+
+/// Alpha (0-255) at or above which a pixel counts as opaque
+///
+/// The default cutoff for every inside/outside decision in the crate: SDF
+/// edge seeding, centroid and bounds, mask pasting. Using one value keeps
+/// the SDF's zero crossing and the centroid in agreement about where the
+/// silhouette is. Functions that take a `threshold` argument override it
+/// per call.
+pub const OPAQUE_THRESHOLD: u8 = 128;
+
+/// Default opaque cutoff, see [`OPAQUE_THRESHOLD`]
+#[wasm_bindgen]
+pub fn opaque_threshold() -> u8 {
+    OPAQUE_THRESHOLD
+}
 
 /// Initialize the WASM module
 /// Called automatically by wasm-bindgen
@@ -50,3 +90,94 @@ pub fn version() -> String {
 pub fn health_check() -> bool {
     true
 }
+
+/// Comma-separated list of capabilities compiled into this build
+///
+/// Lets the JS side tell which build it loaded, alongside [`version`]:
+/// `simd128` and `atomics` (shared-memory threads) are WASM target
+/// features, `safe` and `console_error_panic_hook` are crate features.
+/// Empty for a plain scalar build.
+#[wasm_bindgen]
+pub fn build_features() -> String {
+    let features = [
+        ("simd128", cfg!(target_feature = "simd128")),
+        ("atomics", cfg!(target_feature = "atomics")),
+        ("safe", cfg!(feature = "safe")),
+        ("console_error_panic_hook", cfg!(feature = "console_error_panic_hook")),
+    ];
+
+    features
+        .iter()
+        .filter(|(_, enabled)| *enabled)
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Rough operation count for processing one frame, for capacity planning
+///
+/// A heuristic from the algorithms' complexities, not a timing: the
+/// mipmap chain touches each pixel of every level (about `4/3 · n` for a
+/// full chain of an `n`-pixel image), and the SDF costs one seeding scan
+/// plus `log2(max(w, h))` jump flood passes probing 8 neighbors per pixel
+/// (`~ n · log n · 8`). Compare estimates against each other, or calibrate
+/// once against a measured run on the target device.
+///
+/// # Arguments
+///
+/// * `width` - Image width
+/// * `height` - Image height
+/// * `levels` - Mipmap levels, clamped to
+///   [`max_mipmap_levels`](mipmap::max_mipmap_levels); 0 for no mipmaps
+/// * `include_sdf` - Whether an SDF is generated too
+#[wasm_bindgen]
+pub fn estimate_cost(width: u32, height: u32, levels: u32, include_sdf: bool) -> f32 {
+    let pixels = width as f64 * height as f64;
+
+    // Each level has a quarter of the pixels of the one above
+    let levels = levels.min(mipmap::max_mipmap_levels(width, height));
+    let mipmaps: f64 = (0..levels).map(|level| pixels / 4f64.powi(level as i32)).sum();
+
+    let sdf = if include_sdf && pixels > 0.0 {
+        let passes = (width.max(height) as f64).log2().ceil().max(1.0);
+        pixels + pixels * passes * 8.0
+    } else {
+        0.0
+    };
+
+    (mipmaps + sdf) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_features() {
+        let features = build_features();
+        let listed: Vec<&str> = features.split(',').filter(|f| !f.is_empty()).collect();
+
+        assert_eq!(listed.contains(&"safe"), cfg!(feature = "safe"));
+        assert_eq!(listed.contains(&"simd128"), cfg!(target_feature = "simd128"));
+        assert!(!features.starts_with(',') && !features.ends_with(','));
+    }
+
+    #[test]
+    fn test_estimate_cost() {
+        // Full mipmap chain: about 4/3 of the pixel count, 4x per doubling
+        let small = estimate_cost(256, 256, 32, false);
+        let large = estimate_cost(512, 512, 32, false);
+        assert!((small / (256.0 * 256.0) - 4.0 / 3.0).abs() < 0.01);
+        assert!((large / small - 4.0).abs() < 0.01);
+
+        // The SDF's log factor makes doubling cost a little more than 4x
+        let small = estimate_cost(256, 256, 1, true);
+        let large = estimate_cost(512, 512, 1, true);
+        let ratio = large / small;
+        assert!(ratio > 4.0 && ratio < 5.0, "{}", ratio);
+        assert!(estimate_cost(256, 256, 0, true) > estimate_cost(256, 256, 10, false));
+
+        assert_eq!(estimate_cost(0, 0, 4, true), 0.0);
+        assert_eq!(estimate_cost(64, 64, 0, false), 0.0);
+    }
+}
@@ -0,0 +1,338 @@
+//! Mask Utilities
+//!
+//! Compact encodings for single-channel masks (SDF thresholds, coverage)
+//! so they are cheap to cache and to pass between a worker and the main
+//! thread, plus change detection and similarity scoring between masks.
+
+use wasm_bindgen::prelude::*;
+
+use crate::error::{check_len, Error};
+use crate::normalize::Bounds;
+
+/// Run-length encode a single-channel mask
+///
+/// Output is a sequence of `(run_length, value)` byte pairs, with runs of
+/// 1-255. Binary and sparse masks, which are mostly long runs of 0 or 255,
+/// shrink by orders of magnitude; noisy data can double in size.
+#[wasm_bindgen]
+pub fn rle_encode(mask: &[u8]) -> Vec<u8> {
+    let mut result = Vec::new();
+    let mut iter = mask.iter().copied();
+
+    let Some(mut value) = iter.next() else {
+        return result;
+    };
+    let mut run = 1u8;
+
+    for next in iter {
+        if next == value && run < u8::MAX {
+            run += 1;
+        } else {
+            result.push(run);
+            result.push(value);
+            value = next;
+            run = 1;
+        }
+    }
+    result.push(run);
+    result.push(value);
+
+    result
+}
+
+/// Decode a mask produced by [`rle_encode`]
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidEncoding`] if the data isn't whole
+/// `(run_length, value)` pairs or contains a zero-length run.
+#[wasm_bindgen]
+pub fn rle_decode(encoded: &[u8]) -> Result<Vec<u8>, Error> {
+    if !encoded.len().is_multiple_of(2) {
+        return Err(Error::InvalidEncoding {
+            reason: "RLE data must be (run, value) pairs",
+        });
+    }
+
+    let mut result = Vec::new();
+    for pair in encoded.chunks_exact(2) {
+        if pair[0] == 0 {
+            return Err(Error::InvalidEncoding {
+                reason: "RLE run length of zero",
+            });
+        }
+        result.resize(result.len() + pair[0] as usize, pair[1]);
+    }
+
+    Ok(result)
+}
+
+/// Round a byte to the nearest of 16 evenly spaced levels (0-15)
+fn quantize_4bit(value: u8) -> u8 {
+    ((value as u32 * 15 + 127) / 255) as u8
+}
+
+/// Pack an encoded SDF (or any mask) into 4 bits per pixel
+///
+/// Each byte is rounded to the nearest of 16 levels (0, 17, 34, … 255),
+/// and two pixels share one output byte, first pixel in the high nibble;
+/// an odd final pixel leaves the low nibble zero. The largest error is 8
+/// levels, but inside/outside survives: every value below 128 lands on a
+/// level below 128 and every value from 128 up on one at or above it, so
+/// the SDF's zero crossing stays put.
+#[wasm_bindgen]
+pub fn pack_sdf_4bit(sdf: &[u8]) -> Vec<u8> {
+    sdf.chunks(2)
+        .map(|pair| {
+            let low = pair.get(1).map_or(0, |&v| quantize_4bit(v));
+            (quantize_4bit(pair[0]) << 4) | low
+        })
+        .collect()
+}
+
+/// Expand data produced by [`pack_sdf_4bit`] back to one byte per pixel
+///
+/// Each 4-bit level `q` becomes `q * 17`, so level 15 is 255.
+///
+/// # Arguments
+///
+/// * `packed` - Packed data, two pixels per byte
+/// * `pixel_count` - Number of pixels originally packed
+///
+/// # Errors
+///
+/// Returns [`Error::LengthMismatch`] if `packed` isn't
+/// `ceil(pixel_count / 2)` bytes.
+#[wasm_bindgen]
+pub fn unpack_sdf_4bit(packed: &[u8], pixel_count: usize) -> Result<Vec<u8>, Error> {
+    check_len("packed", packed.len(), pixel_count.div_ceil(2))?;
+
+    Ok(packed
+        .iter()
+        .flat_map(|&byte| [(byte >> 4) * 17, (byte & 0x0F) * 17])
+        .take(pixel_count)
+        .collect())
+}
+
+/// Bounding rectangle of the pixels that changed between two masks
+///
+/// Feed the result to a dirty-region update so only the changed area is
+/// reprocessed.
+///
+/// # Arguments
+///
+/// * `a` - Previous mask, `width * height` bytes
+/// * `b` - Current mask, `width * height` bytes
+/// * `width` - Mask width
+/// * `height` - Mask height
+/// * `threshold` - Largest per-pixel difference still treated as unchanged
+///
+/// # Returns
+///
+/// Tight bounds of every pixel whose values differ by more than
+/// `threshold`; zero-sized bounds at the origin if nothing changed.
+///
+/// # Errors
+///
+/// Returns [`Error::LengthMismatch`] if either mask isn't `width * height`.
+#[wasm_bindgen]
+pub fn diff_mask(
+    a: &[u8],
+    b: &[u8],
+    width: u32,
+    height: u32,
+    threshold: u8,
+) -> Result<Bounds, Error> {
+    let w = width as usize;
+    let h = height as usize;
+
+    check_len("a", a.len(), w * h)?;
+    check_len("b", b.len(), w * h)?;
+
+    let mut min_x = w;
+    let mut max_x = 0usize;
+    let mut min_y = h;
+    let mut max_y = 0usize;
+
+    for y in 0..h {
+        for x in 0..w {
+            let idx = y * w + x;
+            if a[idx].abs_diff(b[idx]) > threshold {
+                min_x = min_x.min(x);
+                max_x = max_x.max(x);
+                min_y = min_y.min(y);
+                max_y = max_y.max(y);
+            }
+        }
+    }
+
+    if min_x > max_x {
+        return Ok(Bounds {
+            x: 0,
+            y: 0,
+            width: 0,
+            height: 0,
+        });
+    }
+
+    Ok(Bounds {
+        x: min_x as u32,
+        y: min_y as u32,
+        width: (max_x - min_x + 1) as u32,
+        height: (max_y - min_y + 1) as u32,
+    })
+}
+
+/// Structural similarity (SSIM) of two single-channel images
+///
+/// A perceptual regression metric for comparing processed mattes (or
+/// luminance): 1.0 for identical inputs, lower as local structure, contrast
+/// or brightness diverge. Uses the standard constants over a 7×7 sliding
+/// window (the whole image if it is smaller), averaged over every window
+/// position.
+///
+/// # Arguments
+///
+/// * `a` - First image, `width * height` bytes
+/// * `b` - Second image, `width * height` bytes
+/// * `width` - Image width
+/// * `height` - Image height
+///
+/// # Errors
+///
+/// Returns [`Error::LengthMismatch`] if either image isn't `width * height`.
+#[wasm_bindgen]
+pub fn ssim(a: &[u8], b: &[u8], width: u32, height: u32) -> Result<f32, Error> {
+    const WINDOW: usize = 7;
+    const C1: f64 = (0.01 * 255.0) * (0.01 * 255.0);
+    const C2: f64 = (0.03 * 255.0) * (0.03 * 255.0);
+
+    let w = width as usize;
+    let h = height as usize;
+    check_len("a", a.len(), w * h)?;
+    check_len("b", b.len(), w * h)?;
+
+    if w == 0 || h == 0 {
+        return Ok(1.0);
+    }
+
+    let win_w = WINDOW.min(w);
+    let win_h = WINDOW.min(h);
+    let n = (win_w * win_h) as f64;
+
+    let mut total = 0.0f64;
+    let mut windows = 0usize;
+
+    for y0 in 0..=h - win_h {
+        for x0 in 0..=w - win_w {
+            let (mut sa, mut sb, mut saa, mut sbb, mut sab) = (0.0, 0.0, 0.0, 0.0, 0.0);
+            for y in y0..y0 + win_h {
+                for x in x0..x0 + win_w {
+                    let va = a[y * w + x] as f64;
+                    let vb = b[y * w + x] as f64;
+                    sa += va;
+                    sb += vb;
+                    saa += va * va;
+                    sbb += vb * vb;
+                    sab += va * vb;
+                }
+            }
+
+            let (mean_a, mean_b) = (sa / n, sb / n);
+            let var_a = saa / n - mean_a * mean_a;
+            let var_b = sbb / n - mean_b * mean_b;
+            let cov = sab / n - mean_a * mean_b;
+
+            total += ((2.0 * mean_a * mean_b + C1) * (2.0 * cov + C2))
+                / ((mean_a * mean_a + mean_b * mean_b + C1) * (var_a + var_b + C2));
+            windows += 1;
+        }
+    }
+
+    Ok((total / windows as f64) as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::disc;
+
+    #[test]
+    fn test_rle_round_trip() {
+        // 64x64 mask, zero except a small 8x8 square
+        let mut mask = vec![0u8; 64 * 64];
+        for y in 20..28 {
+            for x in 30..38 {
+                mask[y * 64 + x] = 255;
+            }
+        }
+
+        let encoded = rle_encode(&mask);
+        assert!(encoded.len() * 20 < mask.len(), "{} bytes", encoded.len());
+        assert_eq!(rle_decode(&encoded).unwrap(), mask);
+
+        assert!(rle_encode(&[]).is_empty());
+        assert!(rle_decode(&[3]).is_err());
+        assert!(rle_decode(&[0, 7]).is_err());
+    }
+
+    #[test]
+    fn test_sdf_4bit_round_trip() {
+        // Every byte value, odd length so the last nibble is padding
+        let sdf: Vec<u8> = (0..=255).chain([128]).collect();
+
+        let packed = pack_sdf_4bit(&sdf);
+        assert_eq!(packed.len(), 129);
+
+        let unpacked = unpack_sdf_4bit(&packed, sdf.len()).unwrap();
+        assert_eq!(unpacked.len(), sdf.len());
+        for (&original, &value) in sdf.iter().zip(&unpacked) {
+            let level = (original as f32 / 17.0).round() as u8;
+            assert_eq!(value, level * 17, "value {}", original);
+            assert_eq!(value >= 128, original >= 128, "value {}", original);
+        }
+        assert_eq!(packed[128] & 0x0F, 0);
+
+        // Already-quantized data is lossless
+        assert_eq!(unpack_sdf_4bit(&pack_sdf_4bit(&unpacked), sdf.len()).unwrap(), unpacked);
+        assert!(unpack_sdf_4bit(&packed, 300).is_err());
+    }
+
+    #[test]
+    fn test_ssim() {
+        // Hard-edged disc
+        let size = 32usize;
+        let disc = disc((size, size), (16.0, 16.0), 8.0);
+
+        let same = ssim(&disc, &disc, size as u32, size as u32).unwrap();
+        assert!((same - 1.0).abs() < 1e-6, "ssim {}", same);
+
+        let blurred = crate::filter::box_blur(&disc, size, size, 3);
+        let score = ssim(&disc, &blurred, size as u32, size as u32).unwrap();
+        assert!(score < 0.9, "ssim {}", score);
+
+        assert!(ssim(&disc, &disc[1..], size as u32, size as u32).is_err());
+    }
+
+    #[test]
+    fn test_diff_mask_corner() {
+        let a = vec![0u8; 32 * 24];
+        let mut b = a.clone();
+
+        // Change a 3x2 patch in the bottom-right corner, plus sub-threshold noise
+        for y in 22..24 {
+            for x in 29..32 {
+                b[y * 32 + x] = 200;
+            }
+        }
+        b[0] = 4;
+
+        let bounds = diff_mask(&a, &b, 32, 24, 8).unwrap();
+        assert_eq!(bounds, Bounds { x: 29, y: 22, width: 3, height: 2 });
+
+        let same = diff_mask(&a, &a, 32, 24, 0).unwrap();
+        assert_eq!((same.width, same.height), (0, 0));
+
+        assert!(diff_mask(&a, &b[1..], 32, 24, 8).is_err());
+    }
+}
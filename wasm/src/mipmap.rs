@@ -9,12 +9,17 @@
 
 use wasm_bindgen::prelude::*;
 
+use crate::color::{decode_srgb, encode_srgb, linear_to_srgb, srgb_to_linear, to_u16, to_u8};
+use crate::error::{check_len, check_region, Error};
+
 /// Mipmap level data
 #[wasm_bindgen]
 pub struct MipmapLevel {
     data: Vec<u8>,
     width: u32,
     height: u32,
+    row_pitch: u32,
+    alpha: Vec<u8>,
 }
 
 #[wasm_bindgen]
@@ -33,6 +38,241 @@ impl MipmapLevel {
     pub fn height(&self) -> u32 {
         self.height
     }
+
+    /// Bytes per row in `data`, including any alignment padding
+    #[wasm_bindgen(getter)]
+    pub fn row_pitch(&self) -> u32 {
+        self.row_pitch
+    }
+
+    /// Tightly packed alpha channel of this level, `width * height` bytes
+    ///
+    /// Empty unless generated with [`MipmapOptions::alpha_pyramid`].
+    #[wasm_bindgen(getter)]
+    pub fn alpha(&self) -> Vec<u8> {
+        self.alpha.clone()
+    }
+
+    /// Length of `data` in bytes, including row padding, without copying it
+    #[wasm_bindgen(getter)]
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Whether `data` is empty (a zero-sized level)
+    #[wasm_bindgen(getter)]
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+}
+
+impl MipmapLevel {
+    /// RGBA bytes of the pixel at (`x`, `y`), read in place
+    ///
+    /// For Rust-side samplers that walk a pyramid without the copy made by
+    /// [`MipmapLevel::data`]. Honors `row_pitch`. Only meaningful for RGBA
+    /// levels, not single-channel ones such as SDF pyramid levels.
+    ///
+    /// # Panics
+    ///
+    /// Panics if (`x`, `y`) is outside the level.
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        assert!(x < self.width && y < self.height, "pixel ({}, {}) out of bounds", x, y);
+        let start = (y * self.row_pitch + x * 4) as usize;
+        let mut px = [0u8; 4];
+        px.copy_from_slice(&self.data[start..start + 4]);
+        px
+    }
+
+    /// Wrap a tightly packed single-channel image
+    pub(crate) fn from_channel(data: Vec<u8>, width: u32, height: u32) -> MipmapLevel {
+        MipmapLevel {
+            data,
+            width,
+            height,
+            row_pitch: width,
+            alpha: Vec::new(),
+        }
+    }
+
+    /// Wrap tightly packed RGBA pixels, padding rows to `row_alignment` bytes
+    fn from_packed(data: &[u8], width: u32, height: u32, row_alignment: u32) -> MipmapLevel {
+        let row_bytes = width * 4;
+        let alignment = row_alignment.max(1);
+        let row_pitch = row_bytes.div_ceil(alignment) * alignment;
+
+        if row_pitch == row_bytes {
+            return MipmapLevel {
+                data: data.to_vec(),
+                width,
+                height,
+                row_pitch,
+                alpha: Vec::new(),
+            };
+        }
+
+        let mut padded = vec![0u8; (row_pitch * height) as usize];
+        for (src, dst) in data
+            .chunks_exact(row_bytes as usize)
+            .zip(padded.chunks_exact_mut(row_pitch as usize))
+        {
+            dst[..src.len()].copy_from_slice(src);
+        }
+
+        MipmapLevel {
+            data: padded,
+            width,
+            height,
+            row_pitch,
+            alpha: Vec::new(),
+        }
+    }
+}
+
+/// Mipmap level with 16 bits per channel
+#[wasm_bindgen]
+pub struct MipmapLevel16 {
+    data: Vec<u16>,
+    width: u32,
+    height: u32,
+}
+
+#[wasm_bindgen]
+impl MipmapLevel16 {
+    /// Tightly packed RGBA, 4 values per pixel (a `Uint16Array` in JS)
+    #[wasm_bindgen(getter)]
+    pub fn data(&self) -> Vec<u16> {
+        self.data.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+}
+
+/// How downsampling treats an odd trailing row or column
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EdgeMode {
+    /// Halve dimensions rounding down, dropping an odd trailing row or
+    /// column (so a 5px border column never reaches the next level)
+    Truncate,
+    /// Halve dimensions rounding up; the missing half of a border block
+    /// replicates the border, so edge colors carry through unchanged
+    Clamp,
+    /// Halve dimensions rounding up; the missing half of a border block is
+    /// transparent black, fading coverage at the edge
+    Zero,
+}
+
+/// Options controlling mipmap generation
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug)]
+pub struct MipmapOptions {
+    /// Input RGB is already multiplied by alpha. Samples are unpremultiplied
+    /// before linearization and re-premultiplied on output, so edges are not
+    /// darkened by running premultiplied values through the sRGB curve.
+    pub input_premultiplied: bool,
+    /// Byte alignment of each output row (e.g. 256 for WebGPU `bytesPerRow`).
+    /// Rows are zero-padded up to a multiple of this; 1 means tightly packed.
+    pub row_alignment: u32,
+    /// Also fill each level's `alpha` with its alpha channel, taken from the
+    /// same downsampled pixels so coverage and color stay aligned.
+    pub alpha_pyramid: bool,
+    /// Treatment of odd dimensions when halving
+    pub edge_mode: EdgeMode,
+    /// Weight each sample's (straight) color by its alpha, so transparent
+    /// pixels don't pull edge colors toward their often-black RGB. Alpha is
+    /// still averaged normally and the output stays straight alpha. Implied
+    /// by `input_premultiplied`, `output_premultiplied` and `preserve_thin`.
+    pub alpha_weighted: bool,
+    /// Unsharp-mask gain applied to every level after the first, countering
+    /// the softening of repeated box filtering (0 disables; 0.2-0.5 is a
+    /// light touch). Sharpening happens in linear premultiplied space and
+    /// color is clamped to coverage, so edges neither darken nor ring into
+    /// transparency. Each level is still downsampled from the unsharpened
+    /// one, so the effect doesn't compound down the pyramid.
+    pub sharpen: f32,
+    /// Emit every level (including level 0) with RGB multiplied by alpha,
+    /// ready for GPU upload, from straight-alpha input. Levels are
+    /// downsampled alpha-weighted in straight alpha and premultiplied only
+    /// on output. Premultiplied input already produces premultiplied
+    /// levels, so this has no further effect with `input_premultiplied`.
+    pub output_premultiplied: bool,
+    /// Keep thin opaque features (1px lines, antennae) from fading: any
+    /// 2×2 block containing a fully opaque pixel outputs full opacity,
+    /// while color is still averaged. Blocks without one average alpha as
+    /// usual, so this stops well short of a max-alpha pyramid. Implies
+    /// `alpha_weighted`, so raised pixels take the feature's color rather
+    /// than its transparent neighbors' RGB.
+    pub preserve_thin: bool,
+}
+
+impl Default for MipmapOptions {
+    fn default() -> Self {
+        MipmapOptions {
+            input_premultiplied: false,
+            row_alignment: 1,
+            alpha_pyramid: false,
+            edge_mode: EdgeMode::Truncate,
+            alpha_weighted: false,
+            sharpen: 0.0,
+            output_premultiplied: false,
+            preserve_thin: false,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl MipmapOptions {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> MipmapOptions {
+        MipmapOptions::default()
+    }
+}
+
+/// Maximum meaningful number of mipmap levels for an image
+///
+/// `floor(log2(max(width, height))) + 1`: halving continues until both
+/// dimensions reach 1, so the last level is 1×1. Every generator clamps
+/// `levels` to `1..=` this: asking for more simply yields the full
+/// pyramid, and level 0 (the original) is always emitted, even for 0.
+/// With [`EdgeMode::Clamp`] or [`EdgeMode::Zero`], odd dimensions round up
+/// and non-power-of-two images get one more level. An empty image (either
+/// dimension 0) has only its base level.
+#[wasm_bindgen]
+pub fn max_mipmap_levels(width: u32, height: u32) -> u32 {
+    if width == 0 || height == 0 {
+        return 1;
+    }
+    width.max(height).ilog2() + 1
+}
+
+/// [`max_mipmap_levels`] for the rounding of `edge_mode`
+fn level_limit(width: u32, height: u32, edge_mode: EdgeMode) -> u32 {
+    if width == 0 || height == 0 {
+        return 1;
+    }
+    match edge_mode {
+        EdgeMode::Truncate => max_mipmap_levels(width, height),
+        EdgeMode::Clamp | EdgeMode::Zero => {
+            width.max(height).max(1).next_power_of_two().ilog2() + 1
+        }
+    }
+}
+
+/// Size of a dimension after one halving step
+fn half_extent(size: usize, edge_mode: EdgeMode) -> usize {
+    match edge_mode {
+        EdgeMode::Truncate => (size / 2).max(1),
+        EdgeMode::Clamp | EdgeMode::Zero => size.div_ceil(2).max(1),
+    }
 }
 
 /// Generate mipmap pyramid from RGBA image data
@@ -42,11 +282,13 @@ impl MipmapLevel {
 /// * `image_data` - RGBA pixel data (4 bytes per pixel)
 /// * `width` - Image width in pixels
 /// * `height` - Image height in pixels
-/// * `levels` - Number of mipmap levels to generate
+/// * `levels` - Number of mipmap levels to generate, clamped to
+///   [`max_mipmap_levels`]
 ///
 /// # Returns
 ///
-/// Array of MipmapLevel, from full resolution down to smallest
+/// Array of MipmapLevel, from full resolution down to smallest. A 1×1 or
+/// empty image yields only its base level.
 #[wasm_bindgen]
 pub fn generate_mipmaps(
     image_data: &[u8],
@@ -54,49 +296,435 @@ pub fn generate_mipmaps(
     height: u32,
     levels: u32,
 ) -> Vec<MipmapLevel> {
-    let mut result = Vec::with_capacity(levels as usize);
+    generate_mipmaps_opts(image_data, width, height, levels, &MipmapOptions::default())
+}
 
-    // Level 0 is the original
-    result.push(MipmapLevel {
-        data: image_data.to_vec(),
-        width,
-        height,
-    });
+/// Generate the complete mipmap chain down to 1×1
+///
+/// [`generate_mipmaps`] with `levels` set to [`max_mipmap_levels`], for
+/// callers who always want the full pyramid and shouldn't have to work
+/// out the count.
+#[wasm_bindgen]
+pub fn generate_mipmaps_full(
+    image_data: &[u8],
+    width: u32,
+    height: u32,
+) -> Vec<MipmapLevel> {
+    generate_mipmaps(image_data, width, height, max_mipmap_levels(width, height))
+}
+
+/// Generate mipmap pyramid with explicit options
+///
+/// # Arguments
+///
+/// * `image_data` - RGBA pixel data (4 bytes per pixel)
+/// * `width` - Image width in pixels
+/// * `height` - Image height in pixels
+/// * `levels` - Number of mipmap levels to generate
+/// * `options` - Filtering options
+#[wasm_bindgen]
+pub fn generate_mipmaps_opts(
+    image_data: &[u8],
+    width: u32,
+    height: u32,
+    levels: u32,
+    options: &MipmapOptions,
+) -> Vec<MipmapLevel> {
+    let mut stepper = generate_mipmaps_stepped(image_data, width, height, levels, options);
+    let mut result = Vec::with_capacity(stepper.levels as usize);
+
+    while let Some(level) = stepper.next_level() {
+        result.push(level);
+    }
+
+    result
+}
+
+/// Generate a mipmap pyramid for one sub-rectangle of an image
+///
+/// Useful for atlases, where only one packed sprite needs a pyramid. The
+/// region is copied out first, so levels are dimensioned from `rw`×`rh`.
+///
+/// # Arguments
+///
+/// * `image_data` - RGBA pixel data (4 bytes per pixel)
+/// * `width` - Image width in pixels
+/// * `height` - Image height in pixels
+/// * `rx`, `ry` - Top-left corner of the region
+/// * `rw`, `rh` - Region size in pixels
+/// * `levels` - Number of mipmap levels to generate
+///
+/// # Errors
+///
+/// Returns [`Error::LengthMismatch`] if `image_data` doesn't match the
+/// dimensions, or [`Error::RegionOutOfBounds`] if the region leaves the
+/// image.
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn generate_mipmaps_region(
+    image_data: &[u8],
+    width: u32,
+    height: u32,
+    rx: u32,
+    ry: u32,
+    rw: u32,
+    rh: u32,
+    levels: u32,
+) -> Result<Vec<MipmapLevel>, Error> {
+    check_len("image_data", image_data.len(), width as usize * height as usize * 4)?;
+    check_region(rx, ry, rw, rh, width, height)?;
+
+    let region = extract_region(image_data, width, 4, rx, ry, rw, rh);
+    Ok(generate_mipmaps(&region, rw, rh, levels))
+}
+
+/// Copy a sub-rectangle out of an image with `channels` bytes per pixel
+///
+/// The region must already be validated to lie inside the image.
+pub(crate) fn extract_region(
+    data: &[u8],
+    width: u32,
+    channels: usize,
+    rx: u32,
+    ry: u32,
+    rw: u32,
+    rh: u32,
+) -> Vec<u8> {
+    let stride = width as usize * channels;
+    let row_bytes = rw as usize * channels;
+    let mut result = Vec::with_capacity(row_bytes * rh as usize);
+
+    for y in ry as usize..(ry + rh) as usize {
+        let start = y * stride + rx as usize * channels;
+        result.extend_from_slice(&data[start..start + row_bytes]);
+    }
+
+    result
+}
+
+/// Generate a mipmap pyramid with a caller-supplied downsample kernel
+///
+/// Each halving step convolves the previous level with `kernel` in linear
+/// light (alpha is filtered unweighted), sampling source texels
+/// `2x + 1 - kernel_size / 2 ..` so the kernel is centered on the 2×2
+/// block it replaces; samples past the border are clamped. Only even
+/// sizes can be centered that way, so odd ones are rejected. Weights are
+/// normalized to sum to 1, so `[1, 1]` and `[0.5, 0.5]` both reproduce
+/// [`generate_mipmaps`] exactly; negative lobes (sharpening) are allowed
+/// and clamped to the valid range on output.
+///
+/// # Arguments
+///
+/// * `image_data` - RGBA pixel data (4 bytes per pixel)
+/// * `width` - Image width in pixels
+/// * `height` - Image height in pixels
+/// * `levels` - Number of mipmap levels to generate
+/// * `kernel` - `kernel_size` weights (separable, applied on both axes) or
+///   `kernel_size²` weights (full 2D, row-major)
+/// * `kernel_size` - Taps per axis; must be even
+///
+/// # Errors
+///
+/// Returns [`Error::LengthMismatch`] if `image_data` doesn't match the
+/// dimensions, or [`Error::InvalidArgument`] if `kernel_size` is zero or
+/// odd, if `kernel` is neither `kernel_size` nor `kernel_size²` weights
+/// long, or if its weights sum to zero.
+#[wasm_bindgen]
+pub fn generate_mipmaps_custom(
+    image_data: &[u8],
+    width: u32,
+    height: u32,
+    levels: u32,
+    kernel: &[f32],
+    kernel_size: u32,
+) -> Result<Vec<MipmapLevel>, Error> {
+    check_len("image_data", image_data.len(), width as usize * height as usize * 4)?;
+
+    let k = kernel_size as usize;
+    if k == 0 || !k.is_multiple_of(2) {
+        return Err(Error::InvalidArgument {
+            name: "kernel_size",
+            reason: "must be even and nonzero to center on each 2×2 block",
+        });
+    }
+
+    // Expand a separable kernel to its 2D outer product
+    let mut weights: Vec<f32> = if kernel.len() == k {
+        (0..k * k).map(|i| kernel[i / k] * kernel[i % k]).collect()
+    } else if kernel.len() == k * k {
+        kernel.to_vec()
+    } else {
+        return Err(Error::InvalidArgument {
+            name: "kernel",
+            reason: "must hold kernel_size (separable) or kernel_size² (2D) weights",
+        });
+    };
+
+    let total: f32 = weights.iter().sum();
+    if !total.is_finite() || total.abs() <= f32::EPSILON {
+        return Err(Error::InvalidArgument {
+            name: "kernel",
+            reason: "weights must have a finite, nonzero sum",
+        });
+    }
+    for weight in &mut weights {
+        *weight /= total;
+    }
 
-    let mut current_width = width;
-    let mut current_height = height;
+    let levels = levels.clamp(1, max_mipmap_levels(width, height));
+    let mut result = Vec::with_capacity(levels as usize);
+    result.push(MipmapLevel::from_packed(image_data, width, height, 1));
+
+    let mut current_width = width as usize;
+    let mut current_height = height as usize;
     let mut current_data = image_data.to_vec();
 
-    // Generate each subsequent level
     for _ in 1..levels {
         let new_width = (current_width / 2).max(1);
         let new_height = (current_height / 2).max(1);
+        let mut new_data = vec![0u8; new_width * new_height * 4];
 
-        let new_data = downsample_2x(
-            &current_data,
-            current_width as usize,
-            current_height as usize,
-        );
+        for y in 0..new_height {
+            for x in 0..new_width {
+                let mut sum = [0.0f32; 4];
 
-        result.push(MipmapLevel {
-            data: new_data.clone(),
-            width: new_width,
-            height: new_height,
-        });
+                for ky in 0..k {
+                    let sy = (2 * y + 1 + ky) as isize - (k / 2) as isize;
+                    let sy = sy.clamp(0, current_height as isize - 1) as usize;
+
+                    for kx in 0..k {
+                        let sx = (2 * x + 1 + kx) as isize - (k / 2) as isize;
+                        let sx = sx.clamp(0, current_width as isize - 1) as usize;
+
+                        let weight = weights[ky * k + kx];
+                        let src = (sy * current_width + sx) * 4;
+                        sum[0] += srgb_to_linear(current_data[src]) * weight;
+                        sum[1] += srgb_to_linear(current_data[src + 1]) * weight;
+                        sum[2] += srgb_to_linear(current_data[src + 2]) * weight;
+                        sum[3] += current_data[src + 3] as f32 * weight;
+                    }
+                }
+
+                let dst = (y * new_width + x) * 4;
+                new_data[dst] = linear_to_srgb(sum[0]);
+                new_data[dst + 1] = linear_to_srgb(sum[1]);
+                new_data[dst + 2] = linear_to_srgb(sum[2]);
+                new_data[dst + 3] = to_u8(sum[3]);
+            }
+        }
+
+        result.push(MipmapLevel::from_packed(
+            &new_data,
+            new_width as u32,
+            new_height as u32,
+            1,
+        ));
 
         current_width = new_width;
         current_height = new_height;
         current_data = new_data;
     }
 
-    result
+    Ok(result)
+}
+
+/// Generate a mipmap pyramid from 16-bit-per-channel RGBA
+///
+/// The 16-bit counterpart of [`generate_mipmaps`]: the same 2×2 box filter
+/// with gamma-correct color, but linearized and re-encoded at full 16-bit
+/// precision, so smooth gradients don't band the way they do when
+/// quantized to 8 bits per level. Color is sRGB-encoded over 0-65535;
+/// alpha is linear.
+///
+/// # Arguments
+///
+/// * `image_data` - RGBA pixel data, 4 values per pixel (a `Uint16Array`)
+/// * `width` - Image width in pixels
+/// * `height` - Image height in pixels
+/// * `levels` - Number of mipmap levels to generate, clamped to
+///   [`max_mipmap_levels`]
+///
+/// # Returns
+///
+/// Array of MipmapLevel16, from full resolution down to smallest
+///
+/// # Errors
+///
+/// Returns [`Error::LengthMismatch`] if `image_data` doesn't match the
+/// dimensions.
+#[wasm_bindgen]
+pub fn generate_mipmaps_u16(
+    image_data: &[u16],
+    width: u32,
+    height: u32,
+    levels: u32,
+) -> Result<Vec<MipmapLevel16>, Error> {
+    check_len("image_data", image_data.len(), width as usize * height as usize * 4)?;
+
+    let options = MipmapOptions::default();
+    let levels = levels.clamp(1, max_mipmap_levels(width, height));
+    let mut result = Vec::with_capacity(levels as usize);
+    result.push(MipmapLevel16 {
+        data: image_data.to_vec(),
+        width,
+        height,
+    });
+
+    for _ in 1..levels {
+        let previous = &result[result.len() - 1];
+        let (w, h) = (previous.width as usize, previous.height as usize);
+        let data = downsample_2x(&previous.data, w, h, &options);
+
+        result.push(MipmapLevel16 {
+            data,
+            width: half_extent(w, options.edge_mode) as u32,
+            height: half_extent(h, options.edge_mode) as u32,
+        });
+    }
+
+    Ok(result)
+}
+
+/// Incremental mipmap generation, one level per call
+///
+/// Retains only the most recent level, from which the next is downsampled,
+/// so a large pyramid can be spread across animation frames to keep the
+/// main thread responsive.
+#[wasm_bindgen]
+pub struct MipmapStepper {
+    current: Vec<u8>,
+    width: u32,
+    height: u32,
+    emitted: u32,
+    levels: u32,
+    options: MipmapOptions,
+}
+
+#[wasm_bindgen]
+impl MipmapStepper {
+    /// Produce the next level, or `None` once all levels have been emitted
+    ///
+    /// The first call returns level 0 (the original image).
+    pub fn next_level(&mut self) -> Option<MipmapLevel> {
+        if self.emitted >= self.levels {
+            return None;
+        }
+
+        // Generate each subsequent level from the one before
+        if self.emitted > 0 {
+            let new_data = downsample_2x(
+                &self.current,
+                self.width as usize,
+                self.height as usize,
+                &self.options,
+            );
+
+            self.width = half_extent(self.width as usize, self.options.edge_mode) as u32;
+            self.height = half_extent(self.height as usize, self.options.edge_mode) as u32;
+            self.current = new_data;
+        }
+
+        let sharpened = (self.emitted > 0 && self.options.sharpen > 0.0).then(|| {
+            sharpen_level(
+                &self.current,
+                self.width as usize,
+                self.height as usize,
+                &self.options,
+            )
+        });
+
+        let straight = sharpened.as_deref().unwrap_or(&self.current);
+        let premultiplied = (self.options.output_premultiplied && !self.options.input_premultiplied)
+            .then(|| premultiply_rgba(straight));
+
+        self.emitted += 1;
+        let mut level = MipmapLevel::from_packed(
+            premultiplied.as_deref().unwrap_or(straight),
+            self.width,
+            self.height,
+            self.options.row_alignment,
+        );
+        if self.options.alpha_pyramid {
+            level.alpha = self.current.chunks_exact(4).map(|px| px[3]).collect();
+        }
+        Some(level)
+    }
+
+    /// Number of levels emitted so far
+    #[wasm_bindgen(getter)]
+    pub fn emitted(&self) -> u32 {
+        self.emitted
+    }
+}
+
+/// Start incremental mipmap generation
+///
+/// Same arguments as [`generate_mipmaps_opts`]. Call
+/// [`MipmapStepper::next_level`] (e.g. once per `requestAnimationFrame`)
+/// until it returns `None`.
+#[wasm_bindgen]
+pub fn generate_mipmaps_stepped(
+    image_data: &[u8],
+    width: u32,
+    height: u32,
+    levels: u32,
+    options: &MipmapOptions,
+) -> MipmapStepper {
+    MipmapStepper {
+        current: image_data.to_vec(),
+        width,
+        height,
+        emitted: 0,
+        levels: levels.clamp(1, level_limit(width, height, options.edge_mode)),
+        options: *options,
+    }
+}
+
+/// Halve an image once with the same gamma-correct filter as the pyramid
+///
+/// For callers managing their own levels: feeding each result back in
+/// reproduces [`generate_mipmaps`] one level at a time. The tuple of
+/// halved pixels and dimensions is returned as a [`MipmapLevel`].
+///
+/// # Arguments
+///
+/// * `image_data` - RGBA pixel data (4 bytes per pixel)
+/// * `width` - Image width in pixels
+/// * `height` - Image height in pixels
+#[wasm_bindgen]
+pub fn downsample_half(
+    image_data: &[u8],
+    width: u32,
+    height: u32,
+) -> MipmapLevel {
+    let options = MipmapOptions::default();
+    let data = downsample_2x(image_data, width as usize, height as usize, &options);
+
+    MipmapLevel::from_packed(
+        &data,
+        half_extent(width as usize, options.edge_mode) as u32,
+        half_extent(height as usize, options.edge_mode) as u32,
+        1,
+    )
 }
 
 /// Downsample RGBA image by 2x using box filter
-fn downsample_2x(data: &[u8], width: usize, height: usize) -> Vec<u8> {
-    let new_width = width / 2;
-    let new_height = height / 2;
-    let mut result = vec![0u8; new_width * new_height * 4];
+///
+/// A dimension that is already 1 stays 1, so non-square pyramids can
+/// continue down to 1×1. Samples past the border (there, and in odd
+/// dimensions rounded up) follow `options.edge_mode`.
+fn downsample_2x<T: Channel>(
+    data: &[T],
+    width: usize,
+    height: usize,
+    options: &MipmapOptions,
+) -> Vec<T> {
+    let new_width = half_extent(width, options.edge_mode);
+    let new_height = half_extent(height, options.edge_mode);
+    let mut result = vec![T::default(); new_width * new_height * 4];
+    // Raised thin features must take their own color, not their
+    // transparent neighbors' RGB
+    let weighted = options.alpha_weighted || options.output_premultiplied || options.preserve_thin;
 
     for y in 0..new_height {
         for x in 0..new_width {
@@ -109,54 +737,232 @@ fn downsample_2x(data: &[u8], width: usize, height: usize) -> Vec<u8> {
             let mut g_sum = 0.0f32;
             let mut b_sum = 0.0f32;
             let mut a_sum = 0.0f32;
+            let mut any_opaque = false;
 
             for dy in 0..2 {
                 for dx in 0..2 {
-                    let src_idx = ((sy + dy) * width + (sx + dx)) * 4;
+                    // Missing samples count as transparent black
+                    let outside = sx + dx >= width || sy + dy >= height;
+                    if outside && options.edge_mode == EdgeMode::Zero {
+                        continue;
+                    }
+
+                    let px = (sx + dx).min(width.saturating_sub(1));
+                    let py = (sy + dy).min(height.saturating_sub(1));
+                    let src_idx = (py * width + px) * 4;
 
                     if src_idx + 3 < data.len() {
-                        // Convert to linear space for proper blending
-                        r_sum += srgb_to_linear(data[src_idx]);
-                        g_sum += srgb_to_linear(data[src_idx + 1]);
-                        b_sum += srgb_to_linear(data[src_idx + 2]);
-                        a_sum += data[src_idx + 3] as f32;
+                        let a = data[src_idx + 3];
+
+                        if options.input_premultiplied {
+                            // Recover straight color, then weight it by
+                            // coverage in linear space
+                            if a > T::default() {
+                                let weight = a.to_f32() / T::SCALE;
+                                r_sum += unpremultiply(data[src_idx], a).to_linear() * weight;
+                                g_sum += unpremultiply(data[src_idx + 1], a).to_linear() * weight;
+                                b_sum += unpremultiply(data[src_idx + 2], a).to_linear() * weight;
+                            }
+                        } else if weighted {
+                            // Weight straight color by coverage in linear space
+                            let weight = a.to_f32() / T::SCALE;
+                            r_sum += data[src_idx].to_linear() * weight;
+                            g_sum += data[src_idx + 1].to_linear() * weight;
+                            b_sum += data[src_idx + 2].to_linear() * weight;
+                        } else {
+                            // Convert to linear space for proper blending
+                            r_sum += data[src_idx].to_linear();
+                            g_sum += data[src_idx + 1].to_linear();
+                            b_sum += data[src_idx + 2].to_linear();
+                        }
+                        a_sum += a.to_f32();
+                        any_opaque |= a.to_f32() == T::SCALE;
                     }
                 }
             }
 
-            // Average and convert back to sRGB
             let dst_idx = (y * new_width + x) * 4;
-            result[dst_idx] = linear_to_srgb(r_sum / 4.0);
-            result[dst_idx + 1] = linear_to_srgb(g_sum / 4.0);
-            result[dst_idx + 2] = linear_to_srgb(b_sum / 4.0);
-            result[dst_idx + 3] = (a_sum / 4.0).round() as u8;
+            let alpha = if options.preserve_thin && any_opaque {
+                T::from_f32(T::SCALE)
+            } else {
+                T::from_f32(a_sum / 4.0)
+            };
+
+            if options.input_premultiplied {
+                // Normalize by coverage, convert back to sRGB, re-premultiply
+                let coverage = a_sum / T::SCALE;
+                if coverage > 0.0 {
+                    let a = alpha.to_f32() / T::SCALE;
+                    result[dst_idx] = premultiply(T::from_linear(r_sum / coverage), a);
+                    result[dst_idx + 1] = premultiply(T::from_linear(g_sum / coverage), a);
+                    result[dst_idx + 2] = premultiply(T::from_linear(b_sum / coverage), a);
+                }
+            } else if weighted {
+                // Normalize by coverage; fully transparent blocks stay black
+                let coverage = a_sum / T::SCALE;
+                if coverage > 0.0 {
+                    result[dst_idx] = T::from_linear(r_sum / coverage);
+                    result[dst_idx + 1] = T::from_linear(g_sum / coverage);
+                    result[dst_idx + 2] = T::from_linear(b_sum / coverage);
+                }
+            } else {
+                // Average and convert back to sRGB
+                result[dst_idx] = T::from_linear(r_sum / 4.0);
+                result[dst_idx + 1] = T::from_linear(g_sum / 4.0);
+                result[dst_idx + 2] = T::from_linear(b_sum / 4.0);
+            }
+            result[dst_idx + 3] = alpha;
         }
     }
 
     result
 }
 
-/// Convert sRGB to linear color space
-fn srgb_to_linear(value: u8) -> f32 {
-    let v = value as f32 / 255.0;
-    if v <= 0.04045 {
-        v / 12.92
-    } else {
-        ((v + 0.055) / 1.055).powf(2.4)
+/// Unsharp-mask one level by `options.sharpen` in linear premultiplied
+/// space, against the mean of each pixel's four neighbors
+///
+/// Alpha is left untouched, and color stays in the level's own
+/// (straight or premultiplied) encoding.
+fn sharpen_level(data: &[u8], width: usize, height: usize, options: &MipmapOptions) -> Vec<u8> {
+    let linear: Vec<[f32; 4]> = data
+        .chunks_exact(4)
+        .map(|px| {
+            let a = px[3] as f32 / 255.0;
+            let channel = |c: u8| {
+                let straight = if options.input_premultiplied && px[3] > 0 {
+                    unpremultiply(c, px[3])
+                } else {
+                    c
+                };
+                srgb_to_linear(straight) * a
+            };
+            [channel(px[0]), channel(px[1]), channel(px[2]), a]
+        })
+        .collect();
+
+    let mut result = data.to_vec();
+    for y in 0..height {
+        for x in 0..width {
+            let idx = y * width + x;
+            let a = linear[idx][3];
+            if a <= 0.0 {
+                continue;
+            }
+
+            let neighbors = [
+                (x.saturating_sub(1), y),
+                ((x + 1).min(width - 1), y),
+                (x, y.saturating_sub(1)),
+                (x, (y + 1).min(height - 1)),
+            ];
+
+            for c in 0..3 {
+                let blur = neighbors
+                    .iter()
+                    .map(|&(nx, ny)| linear[ny * width + nx][c])
+                    .sum::<f32>()
+                    / 4.0;
+                let own = linear[idx][c];
+                let sharpened = (own + options.sharpen * (own - blur)).clamp(0.0, a);
+                let value = linear_to_srgb(sharpened / a);
+
+                result[idx * 4 + c] = if options.input_premultiplied {
+                    premultiply(value, a)
+                } else {
+                    value
+                };
+            }
+        }
     }
+
+    result
 }
 
-/// Convert linear to sRGB color space
-fn linear_to_srgb(value: f32) -> u8 {
-    let v = if value <= 0.0031308 {
-        value * 12.92
-    } else {
-        1.055 * value.powf(1.0 / 2.4) - 0.055
-    };
-    (v * 255.0).clamp(0.0, 255.0).round() as u8
+/// Multiply the RGB of straight-alpha RGBA pixels by their alpha
+fn premultiply_rgba(data: &[u8]) -> Vec<u8> {
+    let mut result = data.to_vec();
+    for px in result.chunks_exact_mut(4) {
+        let a = px[3] as f32 / 255.0;
+        for channel in &mut px[..3] {
+            *channel = premultiply(*channel, a);
+        }
+    }
+    result
+}
+
+/// Recover a straight color channel from a premultiplied one
+fn unpremultiply<T: Channel>(value: T, alpha: T) -> T {
+    T::from_f32(value.to_f32() * T::SCALE / alpha.to_f32())
+}
+
+/// Multiply a straight color channel by alpha (0.0-1.0)
+fn premultiply<T: Channel>(value: T, alpha: f32) -> T {
+    T::from_f32(value.to_f32() * alpha)
+}
+
+/// An 8- or 16-bit channel value the box filter can run over
+///
+/// Color is sRGB-encoded and alpha linear, both over `0..=SCALE`.
+trait Channel: Copy + Default + PartialOrd {
+    /// Full-scale value, i.e. opaque alpha
+    const SCALE: f32;
+
+    fn to_f32(self) -> f32;
+
+    /// Quantize a `0..=SCALE` float
+    fn from_f32(value: f32) -> Self;
+
+    /// Decode sRGB to linear (0.0-1.0)
+    fn to_linear(self) -> f32;
+
+    /// Encode linear (0.0-1.0) to sRGB
+    fn from_linear(value: f32) -> Self;
+}
+
+impl Channel for u8 {
+    const SCALE: f32 = 255.0;
+
+    fn to_f32(self) -> f32 {
+        self as f32
+    }
+
+    fn from_f32(value: f32) -> Self {
+        to_u8(value)
+    }
+
+    fn to_linear(self) -> f32 {
+        srgb_to_linear(self)
+    }
+
+    fn from_linear(value: f32) -> Self {
+        linear_to_srgb(value)
+    }
+}
+
+impl Channel for u16 {
+    const SCALE: f32 = 65535.0;
+
+    fn to_f32(self) -> f32 {
+        self as f32
+    }
+
+    fn from_f32(value: f32) -> Self {
+        to_u16(value)
+    }
+
+    fn to_linear(self) -> f32 {
+        decode_srgb(self as f32 / 65535.0)
+    }
+
+    fn from_linear(value: f32) -> Self {
+        to_u16(encode_srgb(value) * 65535.0)
+    }
 }
 
 /// Select the appropriate mipmap level for a given output size
+///
+/// The result never exceeds `mipmap_count - 1`; a `mipmap_count` of 0 (or
+/// 1) always selects level 0, as does a zero-sized source.
 #[wasm_bindgen]
 pub fn select_mipmap_level(
     output_size: u32,
@@ -169,7 +975,7 @@ pub fn select_mipmap_level(
 
     let ratio = source_size as f32 / output_size as f32;
     let level = ratio.log2().floor() as u32;
-    level.min(mipmap_count - 1)
+    level.min(mipmap_count.saturating_sub(1))
 }
 
 #[cfg(test)]
@@ -187,7 +993,7 @@ mod tests {
             data[i * 4 + 3] = 255; // A
         }
 
-        let result = downsample_2x(&data, 4, 4);
+        let result = downsample_2x(&data, 4, 4, &MipmapOptions::default());
         assert_eq!(result.len(), 2 * 2 * 4);
 
         // Should still be red
@@ -195,11 +1001,485 @@ mod tests {
         assert_eq!(result[3], 255); // A
     }
 
+    #[test]
+    fn test_premultiplied_input() {
+        // 2x2 premultiplied white: two opaque texels, two at alpha 64
+        let data = vec![
+            255, 255, 255, 255, 64, 64, 64, 64,
+            64, 64, 64, 64, 255, 255, 255, 255,
+        ];
+        let options = MipmapOptions {
+            input_premultiplied: true,
+            ..MipmapOptions::default()
+        };
+
+        let levels = generate_mipmaps_opts(&data, 2, 2, 2, &options);
+        let texel = levels[1].data();
+
+        // Still premultiplied white: color equals coverage
+        assert_eq!(texel[3], 160);
+        assert_eq!(&texel[0..3], &[160, 160, 160]);
+
+        // Treating it as straight alpha gamma-skews the color past alpha
+        let naive = generate_mipmaps(&data, 2, 2, 2)[1].data();
+        assert!(naive[0] > naive[3]);
+    }
+
+    #[test]
+    fn test_row_alignment() {
+        // 6x2 image so level 1 is 3 pixels (12 bytes) wide
+        let data: Vec<u8> = (0..6 * 2 * 4).map(|i| i as u8).collect();
+        let options = MipmapOptions {
+            row_alignment: 16,
+            ..MipmapOptions::default()
+        };
+
+        let padded = generate_mipmaps_opts(&data, 6, 2, 2, &options);
+        let packed = generate_mipmaps(&data, 6, 2, 2);
+
+        assert_eq!(padded[0].row_pitch(), 32);
+        assert_eq!(padded[1].row_pitch(), 16);
+        assert_eq!(packed[1].row_pitch(), 12);
+
+        let level = padded[1].data();
+        assert_eq!(level.len(), 16);
+        assert_eq!(&level[..12], &packed[1].data()[..]);
+        assert_eq!(&level[12..], &[0, 0, 0, 0]);
+
+        // Second row of level 0 starts at the padded pitch
+        assert_eq!(&padded[0].data()[32..56], &data[24..48]);
+    }
+
+    #[test]
+    fn test_stepped_matches() {
+        let data: Vec<u8> = (0..16 * 8 * 4).map(|i| (i * 7 % 256) as u8).collect();
+        let options = MipmapOptions::default();
+        let expected = generate_mipmaps(&data, 16, 8, 4);
+
+        let mut stepper = generate_mipmaps_stepped(&data, 16, 8, 4, &options);
+        let mut stepped = Vec::new();
+        while let Some(level) = stepper.next_level() {
+            stepped.push(level);
+        }
+
+        assert_eq!(stepped.len(), expected.len());
+        assert_eq!(stepper.emitted(), 4);
+        for (a, b) in stepped.iter().zip(expected.iter()) {
+            assert_eq!(a.width(), b.width());
+            assert_eq!(a.height(), b.height());
+            assert_eq!(a.data(), b.data());
+        }
+        assert!(stepper.next_level().is_none());
+    }
+
+    #[test]
+    fn test_region_matches_crop() {
+        // 8x8 sheet with a 4x4 sprite at (2, 3)
+        let data: Vec<u8> = (0..8 * 8 * 4).map(|i| (i * 13 % 256) as u8).collect();
+        let mut crop = Vec::new();
+        for y in 3..7 {
+            crop.extend_from_slice(&data[(y * 8 + 2) * 4..(y * 8 + 6) * 4]);
+        }
+
+        let region = generate_mipmaps_region(&data, 8, 8, 2, 3, 4, 4, 3).unwrap();
+        let expected = generate_mipmaps(&crop, 4, 4, 3);
+
+        assert_eq!(region.len(), 3);
+        for (a, b) in region.iter().zip(expected.iter()) {
+            assert_eq!((a.width(), a.height()), (b.width(), b.height()));
+            assert_eq!(a.data(), b.data());
+        }
+
+        assert!(generate_mipmaps_region(&data, 8, 8, 6, 3, 4, 4, 3).is_err());
+
+        // A short buffer is an error rather than a panic
+        let short = &data[..data.len() - 4];
+        assert!(matches!(
+            generate_mipmaps_region(short, 8, 8, 4, 4, 4, 4, 3),
+            Err(Error::LengthMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_custom_kernel() {
+        let data: Vec<u8> = (0..16 * 8 * 4).map(|i| (i * 29 % 256) as u8).collect();
+        let expected = generate_mipmaps(&data, 16, 8, 3);
+
+        // Box filter as a separable and as a 2D kernel
+        let separable = generate_mipmaps_custom(&data, 16, 8, 3, &[0.5, 0.5], 2).unwrap();
+        let full = generate_mipmaps_custom(&data, 16, 8, 3, &[0.25; 4], 2).unwrap();
+        for i in 0..3 {
+            assert_eq!(separable[i].data(), expected[i].data());
+            assert_eq!(full[i].data(), expected[i].data());
+        }
+
+        // Step edge at x = 6: a sharpening kernel overshoots on both sides
+        let mut edge = vec![0u8; 16 * 4 * 4];
+        for (i, px) in edge.chunks_exact_mut(4).enumerate() {
+            let v = if i % 16 < 6 { 64 } else { 192 };
+            px.copy_from_slice(&[v, v, v, 255]);
+        }
+        let sharpen = [-0.125, 0.625, 0.625, -0.125];
+        let sharp = generate_mipmaps_custom(&edge, 16, 4, 2, &sharpen, 4).unwrap();
+        let boxed = generate_mipmaps(&edge, 16, 4, 2);
+
+        let sharp = sharp[1].data();
+        let boxed = boxed[1].data();
+        let contrast = |d: &[u8]| d[3 * 4] as i32 - d[2 * 4] as i32;
+        assert!(contrast(&sharp) > contrast(&boxed));
+
+        assert!(matches!(
+            generate_mipmaps_custom(&data, 16, 8, 3, &[0.5; 3], 2),
+            Err(Error::InvalidArgument { name: "kernel", .. })
+        ));
+        assert!(generate_mipmaps_custom(&data, 16, 8, 3, &[], 0).is_err());
+
+        // Weights are normalized; odd kernels can't center on a 2×2 block
+        let unnormalized = generate_mipmaps_custom(&data, 16, 8, 3, &[1.0, 1.0], 2).unwrap();
+        assert_eq!(unnormalized[2].data(), expected[2].data());
+        assert!(matches!(
+            generate_mipmaps_custom(&data, 16, 8, 3, &[0.25, 0.5, 0.25], 3),
+            Err(Error::InvalidArgument { name: "kernel_size", .. })
+        ));
+        assert!(matches!(
+            generate_mipmaps_custom(&data, 16, 8, 3, &[1.0, -1.0], 2),
+            Err(Error::InvalidArgument { name: "kernel", .. })
+        ));
+
+        // A short buffer is an error, not a panic
+        assert!(matches!(
+            generate_mipmaps_custom(&data[..8], 16, 8, 3, &[0.5, 0.5], 2),
+            Err(Error::LengthMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_alpha_pyramid() {
+        let data: Vec<u8> = (0..16 * 16 * 4).map(|i| (i * 37 % 256) as u8).collect();
+        let options = MipmapOptions {
+            alpha_pyramid: true,
+            ..MipmapOptions::default()
+        };
+        let levels = generate_mipmaps_opts(&data, 16, 16, 4, &options);
+
+        for level in &levels {
+            let alpha: Vec<u8> = level.data().chunks_exact(4).map(|px| px[3]).collect();
+            assert_eq!(level.alpha(), alpha);
+            assert_eq!(level.alpha().len(), (level.width() * level.height()) as usize);
+        }
+
+        // Not requested, not produced
+        assert!(generate_mipmaps(&data, 16, 16, 2)[1].alpha().is_empty());
+    }
+
+    #[test]
+    fn test_levels_clamped() {
+        assert_eq!(max_mipmap_levels(584, 584), 10);
+        assert_eq!(max_mipmap_levels(16, 4), 5);
+        assert_eq!(max_mipmap_levels(1, 1), 1);
+
+        let data = vec![200u8; 584 * 584 * 4];
+        let levels = generate_mipmaps(&data, 584, 584, 20);
+        assert_eq!(levels.len(), 10);
+        let last = levels.last().unwrap();
+        assert_eq!((last.width(), last.height()), (1, 1));
+        assert_eq!(last.data(), vec![200; 4]);
+
+        // Non-square images keep halving the long side down to 1x1
+        let data = vec![90u8; 16 * 4 * 4];
+        let levels = generate_mipmaps(&data, 16, 4, 20);
+        assert_eq!(levels.len(), 5);
+        for level in &levels {
+            assert_eq!(level.data().len(), (level.width() * level.height() * 4) as usize);
+        }
+        assert_eq!((levels[4].width(), levels[4].height()), (1, 1));
+    }
+
+    #[test]
+    fn test_edge_modes_odd_width() {
+        // 5x2: gray body with an opaque red border column at x = 4
+        let mut data = vec![0u8; 5 * 2 * 4];
+        for (i, px) in data.chunks_exact_mut(4).enumerate() {
+            let color = if i % 5 == 4 { [255, 0, 0, 255] } else { [50, 50, 50, 255] };
+            px.copy_from_slice(&color);
+        }
+        let level1 = |edge_mode| {
+            let options = MipmapOptions {
+                edge_mode,
+                ..MipmapOptions::default()
+            };
+            generate_mipmaps_opts(&data, 5, 2, 2, &options).remove(1)
+        };
+
+        // Truncate drops the border column entirely
+        let truncated = level1(EdgeMode::Truncate);
+        assert_eq!(truncated.width(), 2);
+        assert!(truncated.data().chunks_exact(4).all(|px| px == [50, 50, 50, 255]));
+
+        // Clamp keeps it, and its color matches the border exactly
+        let clamped = level1(EdgeMode::Clamp);
+        assert_eq!(clamped.width(), 3);
+        assert_eq!(clamped.data()[8..12], [255, 0, 0, 255]);
+
+        // Zero treats the missing half as transparent black
+        let zeroed = level1(EdgeMode::Zero);
+        assert_eq!(zeroed.data()[11], 128);
+
+        // Rounding up takes 3 halvings to get from 5 to 1
+        let options = MipmapOptions {
+            edge_mode: EdgeMode::Clamp,
+            ..MipmapOptions::default()
+        };
+        let levels = generate_mipmaps_opts(&data, 5, 2, 20, &options);
+        assert_eq!(levels.len(), 4);
+        assert_eq!((levels[3].width(), levels[3].height()), (1, 1));
+    }
+
+    #[test]
+    fn test_alpha_weighted_color() {
+        // Opaque white beside transparent black
+        let data = [255, 255, 255, 255, 0, 0, 0, 0];
+        let options = MipmapOptions {
+            alpha_weighted: true,
+            ..MipmapOptions::default()
+        };
+
+        let weighted = generate_mipmaps_opts(&data, 2, 1, 2, &options);
+        assert_eq!(weighted[1].data(), [255, 255, 255, 128]);
+
+        // The plain average is pulled toward the transparent pixel's black
+        let plain = generate_mipmaps(&data, 2, 1, 2);
+        assert!(plain[1].data()[0] < 255);
+    }
+
+    #[test]
+    fn test_downsample_half_matches_pyramid() {
+        let data: Vec<u8> = (0..12 * 10 * 4).map(|i| (i * 53 % 256) as u8).collect();
+        let levels = generate_mipmaps(&data, 12, 10, 3);
+
+        let half = downsample_half(&data, 12, 10);
+        assert_eq!((half.width(), half.height()), (6, 5));
+        assert_eq!(half.data(), levels[1].data());
+
+        let quarter = downsample_half(&half.data(), half.width(), half.height());
+        assert_eq!(quarter.data(), levels[2].data());
+    }
+
     #[test]
     fn test_level_selection() {
         assert_eq!(select_mipmap_level(512, 512, 4), 0);
         assert_eq!(select_mipmap_level(256, 512, 4), 1);
         assert_eq!(select_mipmap_level(128, 512, 4), 2);
         assert_eq!(select_mipmap_level(64, 512, 4), 3);
+
+        // Clamped to the pyramid, including an empty one
+        assert_eq!(select_mipmap_level(1, 512, 4), 3);
+        assert_eq!(select_mipmap_level(64, 512, 0), 0);
+        assert_eq!(select_mipmap_level(0, 512, 4), 3);
+        assert_eq!(select_mipmap_level(0, 0, 0), 0);
+    }
+
+    #[test]
+    fn test_u16_gradient_no_banding() {
+        // 256x2 opaque ramp spanning just one 8-bit step (4096..4352)
+        let (w, h) = (256usize, 2usize);
+        let data: Vec<u16> = (0..w * h)
+            .flat_map(|i| {
+                let v = 4096 + (i % w) as u16;
+                [v, v, v, 65535]
+            })
+            .collect();
+
+        let levels = generate_mipmaps_u16(&data, w as u32, h as u32, 2).unwrap();
+        assert_eq!((levels[1].width(), levels[1].height()), (128, 1));
+
+        // Every downsampled texel is distinct and increasing
+        let red: Vec<u16> = levels[1].data().chunks_exact(4).map(|px| px[0]).collect();
+        assert!(red.windows(2).all(|pair| pair[1] > pair[0]), "{:?}", red);
+        assert!(levels[1].data().chunks_exact(4).all(|px| px[3] == 65535));
+
+        // The same ramp at 8 bits collapses into a couple of bands
+        let bytes: Vec<u8> = data.iter().map(|&v| (v >> 8) as u8).collect();
+        let coarse = generate_mipmaps(&bytes, w as u32, h as u32, 2)[1].data();
+        let mut bands: Vec<u8> = coarse.chunks_exact(4).map(|px| px[0]).collect();
+        bands.dedup();
+        assert!(bands.len() <= 2, "{:?}", bands);
+
+        // A buffer short of the dimensions is an error, not a panic
+        let result = generate_mipmaps_u16(&data[..data.len() - 4], w as u32, h as u32, 2);
+        assert!(matches!(result, Err(Error::LengthMismatch { .. })));
+    }
+
+    #[test]
+    fn test_sharpen_levels() {
+        // Opaque vertical step (dark left, light right) that lands mid-block
+        // after the first halving, leaving a soft edge at level 2
+        let (w, h) = (16usize, 8usize);
+        let data: Vec<u8> = (0..w * h)
+            .flat_map(|i| {
+                let v = if i % w < 7 { 40 } else { 200 };
+                [v, v, v, 255]
+            })
+            .collect();
+
+        let contrast = |sharpen: f32| {
+            let options = MipmapOptions {
+                sharpen,
+                ..MipmapOptions::default()
+            };
+            let levels = generate_mipmaps_opts(&data, w as u32, h as u32, 3, &options);
+            let level = levels[2].data();
+            (level[2 * 4] as i32 - level[4] as i32, levels[0].data(), level)
+        };
+
+        let (soft, base, plain) = contrast(0.0);
+        let (crisp, sharp_base, sharp) = contrast(0.5);
+        assert!(crisp > soft, "{} vs {}", crisp, soft);
+
+        // Level 0 is untouched and alpha never changes
+        assert_eq!(sharp_base, base);
+        assert!(sharp.chunks_exact(4).zip(plain.chunks_exact(4)).all(|(s, p)| s[3] == p[3]));
+    }
+
+    #[test]
+    fn test_output_premultiplied() {
+        let data: Vec<u8> = (0..16 * 16 * 4).map(|i| (i * 41 % 256) as u8).collect();
+        let straight = MipmapOptions {
+            alpha_weighted: true,
+            ..MipmapOptions::default()
+        };
+        let premultiplied = MipmapOptions {
+            output_premultiplied: true,
+            ..MipmapOptions::default()
+        };
+
+        let expected = generate_mipmaps_opts(&data, 16, 16, 5, &straight);
+        let actual = generate_mipmaps_opts(&data, 16, 16, 5, &premultiplied);
+        assert_eq!(actual.len(), 5);
+
+        for (level, reference) in actual.iter().zip(&expected) {
+            for (px, st) in level.data().chunks_exact(4).zip(reference.data().chunks_exact(4)) {
+                let a = st[3] as f32 / 255.0;
+                assert_eq!(px[3], st[3]);
+                for c in 0..3 {
+                    assert_eq!(px[c], to_u8(st[c] as f32 * a));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_pixel_access() {
+        let data: Vec<u8> = (0..6 * 4 * 4).map(|i| (i * 11 % 256) as u8).collect();
+        let options = MipmapOptions {
+            row_alignment: 16,
+            ..MipmapOptions::default()
+        };
+
+        for level in generate_mipmaps_opts(&data, 6, 4, 3, &options) {
+            let bytes = level.data();
+            assert_eq!(level.len(), bytes.len());
+            assert!(!level.is_empty());
+
+            for y in 0..level.height() {
+                for x in 0..level.width() {
+                    let start = (y * level.row_pitch() + x * 4) as usize;
+                    assert_eq!(level.pixel(x, y), bytes[start..start + 4]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_preserve_thin_line() {
+        // 1px opaque white line at x = 3 in a transparent 8x8 field
+        let data: Vec<u8> = (0..8 * 8)
+            .flat_map(|i| if i % 8 == 3 { [255, 255, 255, 255] } else { [0, 0, 0, 0] })
+            .collect();
+        let options = MipmapOptions {
+            preserve_thin: true,
+            alpha_weighted: true,
+            ..MipmapOptions::default()
+        };
+
+        let kept = generate_mipmaps_opts(&data, 8, 8, 2, &options)[1].data();
+        let faded = generate_mipmaps(&data, 8, 8, 2)[1].data();
+
+        for y in 0..4 {
+            let px = &kept[(y * 4 + 1) * 4..][..4];
+            assert_eq!(px, [255, 255, 255, 255], "row {}", y);
+            assert_eq!(faded[(y * 4 + 1) * 4 + 3], 128);
+        }
+
+        // Blocks with no opaque pixel stay transparent
+        assert_eq!(kept[3], 0);
+
+        // Color is weighted by coverage even without `alpha_weighted`, so a
+        // red line stays red instead of darkening toward its black neighbors
+        let red: Vec<u8> = (0..8 * 8)
+            .flat_map(|i| if i % 8 == 3 { [255, 0, 0, 255] } else { [0, 0, 0, 0] })
+            .collect();
+        let thin_only = MipmapOptions {
+            preserve_thin: true,
+            ..MipmapOptions::default()
+        };
+        let level = generate_mipmaps_opts(&red, 8, 8, 2, &thin_only)[1].data();
+        for y in 0..4 {
+            assert_eq!(&level[(y * 4 + 1) * 4..][..4], [255, 0, 0, 255], "row {}", y);
+        }
+    }
+
+    #[test]
+    fn test_mipmaps_degenerate_sizes() {
+        let one = generate_mipmaps(&[10, 20, 30, 40], 1, 1, 4);
+        assert_eq!(one.len(), 1);
+        assert_eq!(one[0].data(), [10, 20, 30, 40]);
+
+        // Empty images stop at the base level instead of inventing 1×N
+        // levels out of nothing
+        for (w, h) in [(0, 0), (0, 5), (5, 0)] {
+            let levels = generate_mipmaps(&[], w, h, 4);
+            assert_eq!(levels.len(), 1, "{}x{}", w, h);
+            assert!(levels[0].is_empty());
+            assert_eq!(max_mipmap_levels(w, h), 1);
+        }
+        let clamp = MipmapOptions {
+            edge_mode: EdgeMode::Clamp,
+            ..MipmapOptions::default()
+        };
+        assert_eq!(generate_mipmaps_opts(&[], 0, 5, 4, &clamp).len(), 1);
+    }
+
+    #[test]
+    fn test_full_chain() {
+        let image = vec![200u8; 584 * 584 * 4];
+        let levels = generate_mipmaps_full(&image, 584, 584);
+
+        // floor(log2(584)) + 1
+        assert_eq!(levels.len(), 10);
+        let sizes: Vec<u32> = levels.iter().map(|l| l.width()).collect();
+        assert_eq!(sizes, [584, 292, 146, 73, 36, 18, 9, 4, 2, 1]);
+        let last = levels.last().unwrap();
+        assert_eq!((last.width(), last.height()), (1, 1));
+
+        let wide = generate_mipmaps_full(&vec![0; 16 * 4 * 4], 16, 4);
+        assert_eq!(wide.len(), 5);
+        assert_eq!((wide[4].width(), wide[4].height()), (1, 1));
+    }
+
+    #[test]
+    fn test_zero_levels_keeps_base() {
+        // Every generator emits level 0 even when asked for no levels
+        let data: Vec<u8> = (0..8 * 8 * 4).map(|i| i as u8).collect();
+        assert_eq!(generate_mipmaps(&data, 8, 8, 0).len(), 1);
+        assert_eq!(generate_mipmaps(&data, 8, 8, 0)[0].data(), data);
+        assert_eq!(generate_mipmaps_opts(&data, 8, 8, 0, &MipmapOptions::default()).len(), 1);
+        assert_eq!(generate_mipmaps_custom(&data, 8, 8, 0, &[0.5, 0.5], 2).unwrap().len(), 1);
+        assert_eq!(generate_mipmaps_u16(&vec![0; 8 * 8 * 4], 8, 8, 0).unwrap().len(), 1);
+        assert_eq!(crate::sdf::generate_sdf_pyramid(&[0; 64], 8, 8, 4.0, 0).len(), 1);
+
+        let mut stepper = generate_mipmaps_stepped(&data, 8, 8, 0, &MipmapOptions::default());
+        assert!(stepper.next_level().is_some());
+        assert!(stepper.next_level().is_none());
     }
 }
@@ -9,6 +9,13 @@
 
 use wasm_bindgen::prelude::*;
 
+use crate::color::to_u8;
+use crate::error::{check_len, Error};
+use crate::filter::{box_blur, box_mean};
+use crate::sample::bilinear_texel;
+use crate::sdf::{is_edge_pixel, seed_distances, SdfOptions};
+use crate::OPAQUE_THRESHOLD;
+
 /// Result of centroid calculation
 #[wasm_bindgen]
 pub struct Centroid {
@@ -21,6 +28,33 @@ pub struct Centroid {
     pub bounds_height: u32,
 }
 
+#[wasm_bindgen]
+impl Centroid {
+    /// Share of the bounding box covered by opaque pixels
+    ///
+    /// `area / (bounds_width * bounds_height)`: near 1.0 for a solid,
+    /// blob-like shape, low for sparse or spindly ones such as a thin
+    /// diagonal. 0.0 when nothing is opaque.
+    #[wasm_bindgen(getter)]
+    pub fn fill_ratio(&self) -> f32 {
+        let bounds_area = self.bounds_width as u64 * self.bounds_height as u64;
+        if bounds_area == 0 {
+            return 0.0;
+        }
+        (self.area as f64 / bounds_area as f64) as f32
+    }
+}
+
+/// Axis-aligned pixel rectangle
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bounds {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
 /// Calculate centroid and bounding box from alpha mask
 ///
 /// # Arguments
@@ -32,7 +66,9 @@ pub struct Centroid {
 ///
 /// # Returns
 ///
-/// Centroid with position, area, and bounding box
+/// Centroid with position, area, and bounding box. An empty (0-sized)
+/// mask gives zero area, with the position at the origin of its zero
+/// extent.
 #[wasm_bindgen]
 pub fn calculate_centroid(
     alpha_data: &[u8],
@@ -40,266 +76,1694 @@ pub fn calculate_centroid(
     height: u32,
     threshold: u8,
 ) -> Centroid {
-    let w = width as usize;
-    let h = height as usize;
-
-    let mut sum_x = 0.0f64;
-    let mut sum_y = 0.0f64;
-    let mut count = 0u32;
+    // Fast path: fully opaque or fully transparent masks have a known answer
+    if alpha_data.iter().all(|&a| a >= threshold) {
+        return uniform_centroid(width, height, true);
+    }
+    if alpha_data.iter().all(|&a| a < threshold) {
+        return uniform_centroid(width, height, false);
+    }
 
-    let mut min_x = w;
-    let mut max_x = 0usize;
-    let mut min_y = h;
-    let mut max_y = 0usize;
+    accumulate_centroid(alpha_data, width, height, threshold, 255)
+}
 
-    for y in 0..h {
-        for x in 0..w {
-            let idx = y * w + x;
-            if alpha_data[idx] >= threshold {
-                sum_x += x as f64;
-                sum_y += y as f64;
-                count += 1;
+/// Angle of the silhouette's major axis
+///
+/// Takes the covariance of opaque pixel positions about the
+/// [`calculate_centroid`] center and returns the direction of its
+/// principal eigenvector, for rotating sprites upright.
+///
+/// # Arguments
+///
+/// * `alpha_data` - Alpha channel as u8 array
+/// * `width` - Image width
+/// * `height` - Image height
+/// * `threshold` - Alpha threshold (0-255) for considering a pixel "opaque"
+///
+/// # Returns
+///
+/// Angle in radians from the +x axis toward +y (down, in image
+/// coordinates), in `(-π/2, π/2]`: 0 for a wide sprite, π/2 for a tall one.
+/// 0 when there is no dominant direction (no opaque pixels, or a
+/// rotationally symmetric shape).
+#[wasm_bindgen]
+pub fn principal_axis(
+    alpha_data: &[u8],
+    width: u32,
+    height: u32,
+    threshold: u8,
+) -> f32 {
+    let centroid = calculate_centroid(alpha_data, width, height, threshold);
+    if centroid.area == 0 {
+        return 0.0;
+    }
 
-                min_x = min_x.min(x);
-                max_x = max_x.max(x);
-                min_y = min_y.min(y);
-                max_y = max_y.max(y);
-            }
+    let w = width as usize;
+    let (cx, cy) = (centroid.x as f64, centroid.y as f64);
+    let (mut sxx, mut syy, mut sxy) = (0.0f64, 0.0f64, 0.0f64);
+    for (idx, &a) in alpha_data.iter().enumerate() {
+        if a >= threshold {
+            let dx = (idx % w) as f64 - cx;
+            let dy = (idx / w) as f64 - cy;
+            sxx += dx * dx;
+            syy += dy * dy;
+            sxy += dx * dy;
         }
     }
 
-    if count == 0 {
-        return Centroid {
-            x: (width / 2) as f32,
-            y: (height / 2) as f32,
-            area: 0,
-            bounds_x: 0,
-            bounds_y: 0,
-            bounds_width: width,
-            bounds_height: height,
-        };
-    }
+    // Eigenvector angle of [[sxx, sxy], [sxy, syy]]
+    (0.5 * (2.0 * sxy).atan2(sxx - syy)) as f32
+}
+
+/// Snap a centroid's position to a grid
+///
+/// Sub-pixel noise in the matte makes the raw centroid of a static sprite
+/// jitter by fractions of a pixel, which shows up as crawl when it drives
+/// a parallax anchor. Rounding `x` and `y` to the nearest multiple of
+/// `grid` gives a perfectly stable anchor; area and bounds are unchanged.
+///
+/// # Arguments
+///
+/// * `centroid` - Centroid to snap
+/// * `grid` - Grid spacing in pixels (e.g. 1.0 or 0.5); 0 or less
+///   returns the centroid unchanged
+#[wasm_bindgen]
+pub fn quantize_centroid(centroid: &Centroid, grid: f32) -> Centroid {
+    let snap = |v: f32| {
+        if grid > 0.0 {
+            (v / grid).round() * grid
+        } else {
+            v
+        }
+    };
 
     Centroid {
-        x: (sum_x / count as f64) as f32,
-        y: (sum_y / count as f64) as f32,
-        area: count,
-        bounds_x: min_x as u32,
-        bounds_y: min_y as u32,
-        bounds_width: (max_x - min_x + 1) as u32,
-        bounds_height: (max_y - min_y + 1) as u32,
+        x: snap(centroid.x),
+        y: snap(centroid.y),
+        area: centroid.area,
+        bounds_x: centroid.bounds_x,
+        bounds_y: centroid.bounds_y,
+        bounds_width: centroid.bounds_width,
+        bounds_height: centroid.bounds_height,
     }
 }
 
-/// Normalize alpha matte edges
+/// Calculate centroid counting only pixels within an alpha band
 ///
-/// Smooths jagged alpha edges and removes noise/fringing.
+/// Lets faint halos (e.g. alpha 10-30 around a soft matte) be excluded
+/// from the area and bounding box without a blunt single threshold.
 ///
 /// # Arguments
 ///
-/// * `image_data` - RGBA pixel data (modified in place)
+/// * `alpha_data` - Alpha channel as u8 array
 /// * `width` - Image width
 /// * `height` - Image height
-/// * `edge_softness` - Amount of edge softening (0.0 = none, 1.0 = maximum)
+/// * `min_threshold` - Lowest alpha (inclusive) that counts as sprite
+/// * `max_threshold` - Highest alpha (inclusive) that counts as sprite
 ///
 /// # Returns
 ///
-/// Centroid of the normalized matte
+/// Centroid with position, area, and bounding box of in-band pixels
 #[wasm_bindgen]
-pub fn normalize_matte(
-    image_data: &mut [u8],
+pub fn calculate_centroid_banded(
+    alpha_data: &[u8],
     width: u32,
     height: u32,
-    edge_softness: f32,
+    min_threshold: u8,
+    max_threshold: u8,
+) -> Centroid {
+    accumulate_centroid(alpha_data, width, height, min_threshold, max_threshold)
+}
+
+/// Calculate centroid with exact integer arithmetic
+///
+/// Sums pixel coordinates in `u64` and divides as integers, so the result
+/// is bit-for-bit reproducible regardless of image size, platform or float
+/// behavior. `x` and `y` are the exact mean rounded half-up to a whole
+/// pixel; area and bounds match [`calculate_centroid`].
+///
+/// # Arguments
+///
+/// * `alpha_data` - Alpha channel as u8 array
+/// * `width` - Image width
+/// * `height` - Image height
+/// * `threshold` - Alpha threshold (0-255) for considering a pixel "opaque"
+#[wasm_bindgen]
+pub fn calculate_centroid_exact(
+    alpha_data: &[u8],
+    width: u32,
+    height: u32,
+    threshold: u8,
 ) -> Centroid {
     let w = width as usize;
-    let h = height as usize;
 
-    // Extract alpha channel
-    let mut alpha: Vec<u8> = vec![0; w * h];
-    for i in 0..(w * h) {
-        alpha[i] = image_data[i * 4 + 3];
-    }
+    let mut sum_x = 0u64;
+    let mut sum_y = 0u64;
+    let mut count = 0u64;
+    let (mut min_x, mut max_x) = (w, 0usize);
+    let (mut min_y, mut max_y) = (height as usize, 0usize);
 
-    // Apply edge smoothing if requested
-    if edge_softness > 0.0 {
-        let smoothed = smooth_alpha(&alpha, w, h, edge_softness);
+    for (idx, &a) in alpha_data.iter().enumerate() {
+        if a >= threshold {
+            let (x, y) = (idx % w, idx / w);
+            sum_x += x as u64;
+            sum_y += y as u64;
+            count += 1;
 
-        // Write back smoothed alpha
-        for i in 0..(w * h) {
-            image_data[i * 4 + 3] = smoothed[i];
+            min_x = min_x.min(x);
+            max_x = max_x.max(x);
+            min_y = min_y.min(y);
+            max_y = max_y.max(y);
         }
+    }
 
-        // Also premultiply RGB by alpha for proper compositing
-        for i in 0..(w * h) {
-            let a = smoothed[i] as f32 / 255.0;
-            image_data[i * 4] = (image_data[i * 4] as f32 * a) as u8;
-            image_data[i * 4 + 1] = (image_data[i * 4 + 1] as f32 * a) as u8;
-            image_data[i * 4 + 2] = (image_data[i * 4 + 2] as f32 * a) as u8;
-        }
+    if count == 0 {
+        return uniform_centroid(width, height, false);
     }
 
-    // Calculate and return centroid
-    let final_alpha: Vec<u8> = (0..(w * h))
-        .map(|i| image_data[i * 4 + 3])
-        .collect();
+    // Round half-up: floor((2 * sum + count) / (2 * count))
+    let rounded = |sum: u64| ((2 * sum + count) / (2 * count)) as f32;
 
-    calculate_centroid(&final_alpha, width, height, 128)
+    Centroid {
+        x: rounded(sum_x),
+        y: rounded(sum_y),
+        area: count as u32,
+        bounds_x: min_x as u32,
+        bounds_y: min_y as u32,
+        bounds_width: bounds_span(min_x, max_x),
+        bounds_height: bounds_span(min_y, max_y),
+    }
 }
 
-/// Smooth alpha channel using separable box blur
-fn smooth_alpha(alpha: &[u8], w: usize, h: usize, strength: f32) -> Vec<u8> {
-    let radius = (strength * 2.0).ceil() as usize;
-    if radius == 0 {
-        return alpha.to_vec();
+/// Approximate centroid from a strided sub-sample of the mask
+///
+/// Only every `stride`-th pixel of every `stride`-th row is read, cutting
+/// the work by `stride²` for very large frames. The result is
+/// deterministic but approximate: the center can be off by up to about
+/// `stride / 2` pixels (much less for large, solid shapes), area is the
+/// sampled count scaled by `stride²`, and bounds snap to the sample grid,
+/// so they can fall short of the true edges by up to `stride - 1`. Detail
+/// thinner than the stride can be missed entirely. A stride of 0 or 1 is
+/// the exact [`calculate_centroid`].
+///
+/// # Arguments
+///
+/// * `alpha_data` - Alpha channel as u8 array
+/// * `width` - Image width
+/// * `height` - Image height
+/// * `threshold` - Alpha threshold (0-255) for considering a pixel "opaque"
+/// * `stride` - Sampling step in pixels along both axes
+#[wasm_bindgen]
+pub fn calculate_centroid_approx(
+    alpha_data: &[u8],
+    width: u32,
+    height: u32,
+    threshold: u8,
+    stride: u32,
+) -> Centroid {
+    if stride <= 1 {
+        return calculate_centroid(alpha_data, width, height, threshold);
     }
 
-    // Horizontal pass
-    let mut temp = vec![0u8; w * h];
-    for y in 0..h {
-        for x in 0..w {
-            let mut sum = 0u32;
-            let mut count = 0u32;
-
-            for dx in 0..=radius * 2 {
-                let sx = (x + dx).saturating_sub(radius);
-                if sx < w {
-                    sum += alpha[y * w + sx] as u32;
-                    count += 1;
-                }
-            }
+    let w = width as usize;
+    let h = height as usize;
+    let step = stride as usize;
 
-            temp[y * w + x] = (sum / count.max(1)) as u8;
-        }
-    }
+    let mut sum_x = 0.0f64;
+    let mut sum_y = 0.0f64;
+    let mut count = 0u64;
+    let (mut min_x, mut max_x) = (w, 0usize);
+    let (mut min_y, mut max_y) = (h, 0usize);
 
-    // Vertical pass
-    let mut result = vec![0u8; w * h];
-    for y in 0..h {
-        for x in 0..w {
-            let mut sum = 0u32;
-            let mut count = 0u32;
-
-            for dy in 0..=radius * 2 {
-                let sy = (y + dy).saturating_sub(radius);
-                if sy < h {
-                    sum += temp[sy * w + x] as u32;
-                    count += 1;
-                }
-            }
+    for y in (0..h).step_by(step) {
+        for x in (0..w).step_by(step) {
+            if alpha_data[y * w + x] >= threshold {
+                sum_x += x as f64;
+                sum_y += y as f64;
+                count += 1;
 
-            result[y * w + x] = (sum / count.max(1)) as u8;
+                min_x = min_x.min(x);
+                max_x = max_x.max(x);
+                min_y = min_y.min(y);
+                max_y = max_y.max(y);
+            }
         }
     }
 
-    // Blend with original based on strength
-    for i in 0..(w * h) {
-        let blended = alpha[i] as f32 * (1.0 - strength) + result[i] as f32 * strength;
-        result[i] = blended.round() as u8;
+    if count == 0 {
+        return uniform_centroid(width, height, false);
     }
 
-    result
+    let area = count * stride as u64 * stride as u64;
+    Centroid {
+        x: (sum_x / count as f64) as f32,
+        y: (sum_y / count as f64) as f32,
+        area: area.min((w * h) as u64) as u32,
+        bounds_x: min_x as u32,
+        bounds_y: min_y as u32,
+        bounds_width: bounds_span(min_x, max_x),
+        bounds_height: bounds_span(min_y, max_y),
+    }
 }
 
-/// Dilate alpha mask (expand edges)
+/// Suggest a crop rectangle that frames the sprite with margins
+///
+/// The crop is centered on the centroid (the visual mass) rather than the
+/// bounding box, reaching far enough on each side to contain the bounding
+/// box plus a margin of `margin_ratio` times the sprite's width (or height)
+/// on every side. The result is clamped to the image, so sprites near a
+/// border get a tighter margin on that side.
+///
+/// # Arguments
+///
+/// * `alpha_data` - Alpha channel as u8 array
+/// * `width` - Image width
+/// * `height` - Image height
+/// * `threshold` - Alpha threshold (0-255) for considering a pixel "opaque"
+/// * `margin_ratio` - Margin per side as a fraction of the sprite size
+///
+/// # Returns
+///
+/// Crop rectangle; the whole image if no pixel is opaque
 #[wasm_bindgen]
-pub fn dilate_alpha(
+pub fn suggest_crop(
     alpha_data: &[u8],
     width: u32,
     height: u32,
-    radius: u32,
-) -> Vec<u8> {
-    let w = width as usize;
-    let h = height as usize;
-    let r = radius as i32;
-
-    let mut result = vec![0u8; w * h];
-
-    for y in 0..h {
-        for x in 0..w {
-            let mut max_val = 0u8;
-
-            for dy in -r..=r {
-                for dx in -r..=r {
-                    // Circular kernel
-                    if dx * dx + dy * dy > r * r {
-                        continue;
-                    }
-
-                    let sx = x as i32 + dx;
-                    let sy = y as i32 + dy;
+    threshold: u8,
+    margin_ratio: f32,
+) -> Bounds {
+    let c = calculate_centroid(alpha_data, width, height, threshold);
+    if c.area == 0 {
+        return Bounds { x: 0, y: 0, width, height };
+    }
 
-                    if sx >= 0 && sx < w as i32 && sy >= 0 && sy < h as i32 {
-                        let idx = (sy as usize) * w + (sx as usize);
-                        max_val = max_val.max(alpha_data[idx]);
-                    }
-                }
-            }
+    let (x, crop_width) = crop_span(c.x, c.bounds_x, c.bounds_width, margin_ratio, width);
+    let (y, crop_height) = crop_span(c.y, c.bounds_y, c.bounds_height, margin_ratio, height);
 
-            result[y * w + x] = max_val;
-        }
+    Bounds {
+        x,
+        y,
+        width: crop_width,
+        height: crop_height,
     }
-
-    result
 }
 
-/// Erode alpha mask (shrink edges)
+/// Crop to the sprite and resize to a fixed output size in one pass
+///
+/// Finds the bounding box of pixels at or above `threshold`, grows it by
+/// `margin` pixels (clamped to the image) and bilinearly resamples it into
+/// an `out_width`×`out_height` buffer. The aspect ratio is preserved: the
+/// crop is scaled to fit and centered, with transparent padding on the
+/// shorter axis. Sampling is premultiplied, so edges don't darken.
+///
+/// # Arguments
+///
+/// * `image_data` - RGBA pixel data (straight alpha)
+/// * `width` - Image width
+/// * `height` - Image height
+/// * `threshold` - Alpha threshold (0-255) for considering a pixel "opaque"
+/// * `out_width` - Output width
+/// * `out_height` - Output height
+/// * `margin` - Padding around the bounding box, in source pixels
+///
+/// # Returns
+///
+/// RGBA pixel data (straight alpha), `out_width * out_height * 4` bytes.
+/// With no opaque pixels the whole image is used.
 #[wasm_bindgen]
-pub fn erode_alpha(
-    alpha_data: &[u8],
+pub fn crop_and_resize(
+    image_data: &[u8],
     width: u32,
     height: u32,
-    radius: u32,
+    threshold: u8,
+    out_width: u32,
+    out_height: u32,
+    margin: u32,
 ) -> Vec<u8> {
     let w = width as usize;
     let h = height as usize;
-    let r = radius as i32;
+    let (ow, oh) = (out_width as usize, out_height as usize);
+    let mut result = vec![0u8; ow * oh * 4];
+    if w == 0 || h == 0 {
+        return result;
+    }
 
-    let mut result = vec![255u8; w * h];
+    let alpha: Vec<u8> = image_data.chunks_exact(4).map(|px| px[3]).collect();
+    let c = calculate_centroid(&alpha, width, height, threshold);
 
-    for y in 0..h {
-        for x in 0..w {
-            let mut min_val = 255u8;
+    let x0 = c.bounds_x.saturating_sub(margin);
+    let y0 = c.bounds_y.saturating_sub(margin);
+    let x1 = (c.bounds_x + c.bounds_width).saturating_add(margin).min(width);
+    let y1 = (c.bounds_y + c.bounds_height).saturating_add(margin).min(height);
+    let (crop_w, crop_h) = ((x1 - x0) as f32, (y1 - y0) as f32);
 
-            for dy in -r..=r {
-                for dx in -r..=r {
-                    // Circular kernel
-                    if dx * dx + dy * dy > r * r {
-                        continue;
-                    }
+    // Fit the crop inside the output and center it
+    let scale = (ow as f32 / crop_w).min(oh as f32 / crop_h);
+    let pad_x = (ow as f32 - crop_w * scale) * 0.5;
+    let pad_y = (oh as f32 - crop_h * scale) * 0.5;
 
-                    let sx = x as i32 + dx;
-                    let sy = y as i32 + dy;
+    for oy in 0..oh {
+        let cy = (oy as f32 + 0.5 - pad_y) / scale;
+        if !(0.0..crop_h).contains(&cy) {
+            continue;
+        }
 
-                    if sx >= 0 && sx < w as i32 && sy >= 0 && sy < h as i32 {
-                        let idx = (sy as usize) * w + (sx as usize);
-                        min_val = min_val.min(alpha_data[idx]);
-                    }
-                }
+        for ox in 0..ow {
+            let cx = (ox as f32 + 0.5 - pad_x) / scale;
+            if !(0.0..crop_w).contains(&cx) {
+                continue;
             }
 
-            result[y * w + x] = min_val;
+            let px = bilinear_texel(
+                image_data,
+                w,
+                h,
+                x0 as f32 + cx - 0.5,
+                y0 as f32 + cy - 0.5,
+                false,
+                false,
+            );
+            let dst = (oy * ow + ox) * 4;
+            result[dst..dst + 4].copy_from_slice(&px);
         }
     }
 
     result
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// One axis of [`suggest_crop`]: start and length of the clamped span
+fn crop_span(centroid: f32, start: u32, span: u32, margin_ratio: f32, limit: u32) -> (u32, u32) {
+    // Work in continuous coordinates, where pixel `i` covers [i, i + 1)
+    let center = centroid + 0.5;
+    let end = (start + span) as f32;
+    let half = (center - start as f32).max(end - center) + span as f32 * margin_ratio.max(0.0);
 
-    #[test]
-    fn test_centroid_empty() {
-        let alpha = vec![0u8; 16];
-        let c = calculate_centroid(&alpha, 4, 4, 128);
-        assert_eq!(c.area, 0);
-        assert_eq!(c.x, 2.0);
-        assert_eq!(c.y, 2.0);
-    }
+    let lo = (center - half).floor().clamp(0.0, limit as f32) as u32;
+    let hi = (center + half).ceil().clamp(0.0, limit as f32) as u32;
+    (lo, hi - lo)
+}
 
-    #[test]
-    fn test_centroid_filled() {
+/// Total fractional coverage of an alpha mask
+///
+/// Sums `alpha / 255` over every pixel, so soft edges count in proportion
+/// to their opacity instead of all-or-nothing as in [`Centroid::area`].
+///
+/// # Returns
+///
+/// Coverage in pixels (a fully opaque 10×10 mask covers 100.0)
+#[wasm_bindgen]
+pub fn coverage_area(alpha_data: &[u8]) -> f32 {
+    // Integer sum keeps large frames exact before the single division
+    let total: u64 = alpha_data.iter().map(|&a| a as u64).sum();
+    (total as f64 / 255.0) as f32
+}
+
+/// Centroid of a mask that is entirely opaque or entirely transparent
+fn uniform_centroid(width: u32, height: u32, opaque: bool) -> Centroid {
+    if !opaque {
+        return Centroid {
+            x: (width / 2) as f32,
+            y: (height / 2) as f32,
+            area: 0,
+            bounds_x: 0,
+            bounds_y: 0,
+            bounds_width: width,
+            bounds_height: height,
+        };
+    }
+
+    Centroid {
+        x: width.saturating_sub(1) as f32 / 2.0,
+        y: height.saturating_sub(1) as f32 / 2.0,
+        area: width * height,
+        bounds_x: 0,
+        bounds_y: 0,
+        bounds_width: width,
+        bounds_height: height,
+    }
+}
+
+/// Full centroid scan accumulating every pixel with alpha in `[min, max]`
+fn accumulate_centroid(
+    alpha_data: &[u8],
+    width: u32,
+    height: u32,
+    min: u8,
+    max: u8,
+) -> Centroid {
+    let w = width as usize;
+    let h = height as usize;
+
+    let mut sum_x = 0.0f64;
+    let mut sum_y = 0.0f64;
+    let mut count = 0u32;
+
+    let mut min_x = w;
+    let mut max_x = 0usize;
+    let mut min_y = h;
+    let mut max_y = 0usize;
+
+    for y in 0..h {
+        for x in 0..w {
+            let idx = y * w + x;
+            if (min..=max).contains(&alpha_data[idx]) {
+                sum_x += x as f64;
+                sum_y += y as f64;
+                count += 1;
+
+                min_x = min_x.min(x);
+                max_x = max_x.max(x);
+                min_y = min_y.min(y);
+                max_y = max_y.max(y);
+            }
+        }
+    }
+
+    if count == 0 {
+        return uniform_centroid(width, height, false);
+    }
+
+    Centroid {
+        x: (sum_x / count as f64) as f32,
+        y: (sum_y / count as f64) as f32,
+        area: count,
+        bounds_x: min_x as u32,
+        bounds_y: min_y as u32,
+        bounds_width: bounds_span(min_x, max_x),
+        bounds_height: bounds_span(min_y, max_y),
+    }
+}
+
+/// Inclusive pixel extent between `min` and `max`
+///
+/// A single pixel spans 1. Inverted bounds (nothing accumulated) span 0
+/// rather than underflowing.
+fn bounds_span(min: usize, max: usize) -> u32 {
+    max.checked_sub(min)
+        .and_then(|d| u32::try_from(d).ok())
+        .map_or(0, |d| d.saturating_add(1))
+}
+
+/// Label 8-connected components of opaque pixels
+///
+/// # Arguments
+///
+/// * `alpha_data` - Alpha channel as u8 array
+/// * `width` - Image width
+/// * `height` - Image height
+/// * `threshold` - Alpha threshold (0-255) for considering a pixel "opaque"
+///
+/// # Returns
+///
+/// Label per pixel: 0 for background, 1..=N for each component in scan order
+#[wasm_bindgen]
+pub fn label_components(
+    alpha_data: &[u8],
+    width: u32,
+    height: u32,
+    threshold: u8,
+) -> Vec<u32> {
+    let w = width as usize;
+    let h = height as usize;
+
+    let mut labels = vec![0u32; w * h];
+    let mut next_label = 0u32;
+    let mut stack = Vec::new();
+
+    for start in 0..(w * h) {
+        if labels[start] != 0 || alpha_data[start] < threshold {
+            continue;
+        }
+
+        next_label += 1;
+        labels[start] = next_label;
+        stack.push(start);
+
+        // Flood fill the component
+        while let Some(idx) = stack.pop() {
+            let x = (idx % w) as i32;
+            let y = (idx / w) as i32;
+
+            for dy in -1i32..=1 {
+                for dx in -1i32..=1 {
+                    let nx = x + dx;
+                    let ny = y + dy;
+
+                    if nx >= 0 && nx < w as i32 && ny >= 0 && ny < h as i32 {
+                        let nidx = (ny as usize) * w + (nx as usize);
+                        if labels[nidx] == 0 && alpha_data[nidx] >= threshold {
+                            labels[nidx] = next_label;
+                            stack.push(nidx);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    labels
+}
+
+/// Running position sums and bounds for one connected component
+struct BlobStats {
+    sum_x: f64,
+    sum_y: f64,
+    area: u32,
+    min_x: usize,
+    min_y: usize,
+    max_x: usize,
+    max_y: usize,
+}
+
+impl BlobStats {
+    fn new() -> BlobStats {
+        BlobStats {
+            sum_x: 0.0,
+            sum_y: 0.0,
+            area: 0,
+            min_x: usize::MAX,
+            min_y: usize::MAX,
+            max_x: 0,
+            max_y: 0,
+        }
+    }
+
+    fn add(&mut self, x: usize, y: usize) {
+        self.sum_x += x as f64;
+        self.sum_y += y as f64;
+        self.area += 1;
+        self.min_x = self.min_x.min(x);
+        self.min_y = self.min_y.min(y);
+        self.max_x = self.max_x.max(x);
+        self.max_y = self.max_y.max(y);
+    }
+
+    fn centroid(&self) -> Centroid {
+        Centroid {
+            x: (self.sum_x / self.area as f64) as f32,
+            y: (self.sum_y / self.area as f64) as f32,
+            area: self.area,
+            bounds_x: self.min_x as u32,
+            bounds_y: self.min_y as u32,
+            bounds_width: bounds_span(self.min_x, self.max_x),
+            bounds_height: bounds_span(self.min_y, self.max_y),
+        }
+    }
+}
+
+/// Calculate a centroid per disjoint sprite in the mask
+///
+/// Runs [`label_components`] and accumulates a [`Centroid`] for each
+/// component, so separate objects are not averaged into one midpoint.
+///
+/// # Arguments
+///
+/// * `alpha_data` - Alpha channel as u8 array
+/// * `width` - Image width
+/// * `height` - Image height
+/// * `threshold` - Alpha threshold (0-255) for considering a pixel "opaque"
+/// * `min_area` - Components with fewer pixels than this are discarded
+///
+/// # Returns
+///
+/// One centroid per component, sorted by area (largest first)
+#[wasm_bindgen]
+pub fn calculate_centroids_multi(
+    alpha_data: &[u8],
+    width: u32,
+    height: u32,
+    threshold: u8,
+    min_area: u32,
+) -> Vec<Centroid> {
+    let w = width as usize;
+    let labels = label_components(alpha_data, width, height, threshold);
+    let count = labels.iter().copied().max().unwrap_or(0) as usize;
+
+    let mut stats: Vec<BlobStats> = (0..count).map(|_| BlobStats::new()).collect();
+    for (idx, &label) in labels.iter().enumerate() {
+        if label != 0 {
+            stats[label as usize - 1].add(idx % w, idx / w);
+        }
+    }
+
+    let mut result: Vec<Centroid> = stats
+        .iter()
+        .filter(|s| s.area >= min_area)
+        .map(BlobStats::centroid)
+        .collect();
+
+    result.sort_by_key(|c| std::cmp::Reverse(c.area));
+    result
+}
+
+/// Convex hull of the opaque silhouette
+///
+/// Collects boundary pixels (opaque edge pixels, plus opaque pixels on the
+/// image border) and runs Andrew's monotonic-chain algorithm over their
+/// centers. Collinear points are dropped, so only true corners remain.
+///
+/// # Arguments
+///
+/// * `alpha_data` - Alpha channel as u8 array
+/// * `width` - Image width
+/// * `height` - Image height
+/// * `threshold` - Alpha threshold (0-255) for considering a pixel "opaque"
+///
+/// # Returns
+///
+/// Hull vertices as flat `[x0, y0, x1, y1, ...]` pixel centers, ordered
+/// around the hull starting from the leftmost point. Empty if nothing is
+/// opaque.
+#[wasm_bindgen]
+pub fn convex_hull(
+    alpha_data: &[u8],
+    width: u32,
+    height: u32,
+    threshold: u8,
+) -> Vec<f32> {
+    let w = width as usize;
+    let h = height as usize;
+
+    // Boundary points, sorted by x then y for the monotonic chain
+    let mut points: Vec<(i64, i64)> = Vec::new();
+    for y in 0..h {
+        for x in 0..w {
+            if alpha_data[y * w + x] < threshold {
+                continue;
+            }
+            let on_border = x == 0 || y == 0 || x == w - 1 || y == h - 1;
+            if on_border || is_edge_pixel(alpha_data, w, h, x, y, threshold) {
+                points.push((x as i64, y as i64));
+            }
+        }
+    }
+    points.sort_unstable();
+
+    if points.len() < 3 {
+        return points.iter().flat_map(|&(x, y)| [x as f32, y as f32]).collect();
+    }
+
+    let cross = |o: (i64, i64), a: (i64, i64), b: (i64, i64)| {
+        (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+    };
+
+    // Lower then upper chain
+    let mut hull: Vec<(i64, i64)> = Vec::with_capacity(points.len() * 2);
+    for &p in points.iter() {
+        while hull.len() >= 2 && cross(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 0 {
+            hull.pop();
+        }
+        hull.push(p);
+    }
+    let lower_len = hull.len() + 1;
+    for &p in points.iter().rev().skip(1) {
+        while hull.len() >= lower_len && cross(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 0 {
+            hull.pop();
+        }
+        hull.push(p);
+    }
+    hull.pop();
+
+    hull.iter().flat_map(|&(x, y)| [x as f32, y as f32]).collect()
+}
+
+/// Normalize alpha matte edges
+///
+/// Smooths jagged alpha edges and removes noise/fringing. When smoothing is
+/// applied, RGB is premultiplied by the new alpha for compositing. This is
+/// lossy: pixels smoothed to near-zero alpha keep almost none of their
+/// color, so unpremultiplying later yields black. Use
+/// [`normalize_matte_straight`] when color must stay recoverable.
+///
+/// # Arguments
+///
+/// * `image_data` - RGBA pixel data (modified in place)
+/// * `width` - Image width
+/// * `height` - Image height
+/// * `edge_softness` - Amount of edge softening (0.0 = none, 1.0 = maximum)
+///
+/// # Returns
+///
+/// Centroid of the normalized matte, counting alpha >= [`OPAQUE_THRESHOLD`]
+#[wasm_bindgen]
+pub fn normalize_matte(
+    image_data: &mut [u8],
+    width: u32,
+    height: u32,
+    edge_softness: f32,
+) -> Centroid {
+    normalize_matte_impl(image_data, width, height, edge_softness, true, OPAQUE_THRESHOLD)
+}
+
+/// Normalize alpha matte edges with a custom opaque cutoff
+///
+/// Same as [`normalize_matte`], but the returned centroid counts pixels
+/// with alpha >= `threshold`. Pass the same value used for the SDF
+/// ([`SdfOptions::threshold`]) so both agree on the silhouette.
+#[wasm_bindgen]
+pub fn normalize_matte_with_threshold(
+    image_data: &mut [u8],
+    width: u32,
+    height: u32,
+    edge_softness: f32,
+    threshold: u8,
+) -> Centroid {
+    normalize_matte_impl(image_data, width, height, edge_softness, true, threshold)
+}
+
+/// Normalize alpha matte edges, keeping straight alpha
+///
+/// Same smoothing as [`normalize_matte`], but RGB is left untouched so the
+/// original color of every edge pixel survives for later decontamination.
+///
+/// # Arguments
+///
+/// * `image_data` - RGBA pixel data (alpha modified in place)
+/// * `width` - Image width
+/// * `height` - Image height
+/// * `edge_softness` - Amount of edge softening (0.0 = none, 1.0 = maximum)
+///
+/// # Returns
+///
+/// Centroid of the normalized matte
+#[wasm_bindgen]
+pub fn normalize_matte_straight(
+    image_data: &mut [u8],
+    width: u32,
+    height: u32,
+    edge_softness: f32,
+) -> Centroid {
+    normalize_matte_impl(image_data, width, height, edge_softness, false, OPAQUE_THRESHOLD)
+}
+
+/// Normalize alpha matte edges into a caller-provided buffer
+///
+/// Same result as [`normalize_matte`] applied to a copy of `src`, written
+/// to `dst` so the original frame is kept and no output is allocated.
+///
+/// # Arguments
+///
+/// * `src` - RGBA pixel data (left untouched)
+/// * `dst` - Receives the normalized RGBA data, same length as `src`
+/// * `width` - Image width
+/// * `height` - Image height
+/// * `edge_softness` - Amount of edge softening (0.0 = none, 1.0 = maximum)
+///
+/// # Errors
+///
+/// Returns [`Error::LengthMismatch`] if `src` or `dst` isn't
+/// `width * height * 4` bytes.
+#[wasm_bindgen]
+pub fn normalize_matte_into(
+    src: &[u8],
+    dst: &mut [u8],
+    width: u32,
+    height: u32,
+    edge_softness: f32,
+) -> Result<Centroid, Error> {
+    let size = width as usize * height as usize * 4;
+    check_len("src", src.len(), size)?;
+    check_len("dst", dst.len(), size)?;
+
+    dst.copy_from_slice(src);
+    Ok(normalize_matte_impl(dst, width, height, edge_softness, true, OPAQUE_THRESHOLD))
+}
+
+/// Shared body of [`normalize_matte`] and [`normalize_matte_straight`]
+fn normalize_matte_impl(
+    image_data: &mut [u8],
+    width: u32,
+    height: u32,
+    edge_softness: f32,
+    premultiply: bool,
+    threshold: u8,
+) -> Centroid {
+    let w = width as usize;
+    let h = height as usize;
+
+    // Fast path: smoothing a uniform 0 or 255 matte leaves alpha unchanged,
+    // so only the premultiply of a transparent matte has any effect
+    let first_alpha = image_data.get(3).copied().unwrap_or(0);
+    if (first_alpha == 0 || first_alpha == 255)
+        && image_data.chunks_exact(4).all(|px| px[3] == first_alpha)
+    {
+        if premultiply && first_alpha == 0 && edge_softness > 0.0 {
+            for px in image_data.chunks_exact_mut(4) {
+                px[..3].fill(0);
+            }
+        }
+        return uniform_centroid(width, height, first_alpha >= threshold);
+    }
+
+    // Extract alpha channel
+    let mut alpha: Vec<u8> = vec![0; w * h];
+    for i in 0..(w * h) {
+        alpha[i] = image_data[i * 4 + 3];
+    }
+
+    // Apply edge smoothing if requested
+    if edge_softness > 0.0 {
+        let smoothed = smooth_alpha(&alpha, w, h, edge_softness);
+
+        // Write back smoothed alpha
+        for i in 0..(w * h) {
+            image_data[i * 4 + 3] = smoothed[i];
+        }
+
+        // Also premultiply RGB by alpha for proper compositing
+        if premultiply {
+            for i in 0..(w * h) {
+                let a = smoothed[i] as f32 / 255.0;
+                image_data[i * 4] = to_u8(image_data[i * 4] as f32 * a);
+                image_data[i * 4 + 1] = to_u8(image_data[i * 4 + 1] as f32 * a);
+                image_data[i * 4 + 2] = to_u8(image_data[i * 4 + 2] as f32 * a);
+            }
+        }
+    }
+
+    // Calculate and return centroid
+    let final_alpha: Vec<u8> = (0..(w * h))
+        .map(|i| image_data[i * 4 + 3])
+        .collect();
+
+    calculate_centroid(&final_alpha, width, height, threshold)
+}
+
+/// Box blur radius used by [`normalize_matte`] for a given softness
+///
+/// The radius grows linearly with softness, two pixels per unit rounded
+/// up: 0 for no smoothing, 1 up to 0.5, 2 up to 1.0. The blurred alpha is
+/// then mixed with the original by `edge_softness` itself, so within one
+/// radius step the softness sets how strongly the blur shows.
+#[wasm_bindgen]
+pub fn smooth_alpha_radius(edge_softness: f32) -> u32 {
+    (edge_softness.max(0.0) * 2.0).ceil() as u32
+}
+
+/// Smooth alpha channel using separable box blur
+fn smooth_alpha(alpha: &[u8], w: usize, h: usize, strength: f32) -> Vec<u8> {
+    let radius = smooth_alpha_radius(strength) as usize;
+    if radius == 0 {
+        return alpha.to_vec();
+    }
+
+    let mut result = box_blur(alpha, w, h, radius);
+
+    // Blend with original based on strength
+    for i in 0..(w * h) {
+        let blended = alpha[i] as f32 * (1.0 - strength) + result[i] as f32 * strength;
+        result[i] = to_u8(blended);
+    }
+
+    result
+}
+
+/// Dilate alpha mask (expand edges)
+#[wasm_bindgen]
+pub fn dilate_alpha(
+    alpha_data: &[u8],
+    width: u32,
+    height: u32,
+    radius: u32,
+) -> Vec<u8> {
+    dilate_alpha_xy(alpha_data, width, height, radius, radius)
+}
+
+/// Erode alpha mask (shrink edges)
+#[wasm_bindgen]
+pub fn erode_alpha(
+    alpha_data: &[u8],
+    width: u32,
+    height: u32,
+    radius: u32,
+) -> Vec<u8> {
+    erode_alpha_xy(alpha_data, width, height, radius, radius)
+}
+
+/// Dilate alpha mask with independent horizontal and vertical radii
+///
+/// Uses an elliptical kernel, `dx²/rx² + dy²/ry² <= 1`; a zero radius
+/// disables growth along that axis, so `ry = 0` smears only horizontally
+/// for directional motion and squash effects.
+#[wasm_bindgen]
+pub fn dilate_alpha_xy(
+    alpha_data: &[u8],
+    width: u32,
+    height: u32,
+    radius_x: u32,
+    radius_y: u32,
+) -> Vec<u8> {
+    morphology(alpha_data, width, height, radius_x, radius_y, true)
+}
+
+/// Erode alpha mask with independent horizontal and vertical radii
+///
+/// Elliptical counterpart of [`erode_alpha`]; see [`dilate_alpha_xy`].
+#[wasm_bindgen]
+pub fn erode_alpha_xy(
+    alpha_data: &[u8],
+    width: u32,
+    height: u32,
+    radius_x: u32,
+    radius_y: u32,
+) -> Vec<u8> {
+    morphology(alpha_data, width, height, radius_x, radius_y, false)
+}
+
+/// Morphological gradient: dilation minus erosion
+///
+/// A quick outline without an SDF: nonzero only within `radius` pixels of
+/// an edge on either side, giving a band about `2 * radius` wide, and 0
+/// across flat interiors and backgrounds.
+#[wasm_bindgen]
+pub fn morphological_gradient(
+    alpha_data: &[u8],
+    width: u32,
+    height: u32,
+    radius: u32,
+) -> Vec<u8> {
+    let dilated = dilate_alpha(alpha_data, width, height, radius);
+    let eroded = erode_alpha(alpha_data, width, height, radius);
+
+    dilated
+        .iter()
+        .zip(&eroded)
+        .map(|(&d, &e)| d.saturating_sub(e))
+        .collect()
+}
+
+/// Dilate alpha mask by a fractional radius
+///
+/// Blends the `floor(radius)` and `ceil(radius)` dilations by the
+/// fractional part, so animating `radius` grows the mask smoothly instead
+/// of in whole-pixel jumps. Integer radii match [`dilate_alpha`] exactly;
+/// negative radii are treated as 0.
+#[wasm_bindgen]
+pub fn dilate_alpha_f(
+    alpha_data: &[u8],
+    width: u32,
+    height: u32,
+    radius: f32,
+) -> Vec<u8> {
+    let radius = radius.max(0.0);
+    let lower = radius.floor();
+    let t = radius - lower;
+
+    let inner = dilate_alpha(alpha_data, width, height, lower as u32);
+    if t == 0.0 {
+        return inner;
+    }
+
+    let outer = dilate_alpha(alpha_data, width, height, lower as u32 + 1);
+    inner
+        .iter()
+        .zip(&outer)
+        .map(|(&a, &b)| to_u8(a as f32 + (b as f32 - a as f32) * t))
+        .collect()
+}
+
+/// Max (dilate) or min (erode) filter over an elliptical kernel
+fn morphology(
+    alpha_data: &[u8],
+    width: u32,
+    height: u32,
+    radius_x: u32,
+    radius_y: u32,
+    dilate: bool,
+) -> Vec<u8> {
+    let w = width as usize;
+    let h = height as usize;
+    let rx = radius_x as i32;
+    let ry = radius_y as i32;
+
+    // dx²/rx² + dy²/ry² <= 1, multiplied through so zero radii are exact
+    let rx2 = (rx as i64) * (rx as i64);
+    let ry2 = (ry as i64) * (ry as i64);
+    let in_kernel = |dx: i32, dy: i32| {
+        (dx as i64 * dx as i64) * ry2 + (dy as i64 * dy as i64) * rx2 <= rx2 * ry2
+    };
+
+    let mut result = vec![0u8; w * h];
+
+    for y in 0..h {
+        for x in 0..w {
+            let mut value = if dilate { 0u8 } else { 255u8 };
+
+            for dy in -ry..=ry {
+                for dx in -rx..=rx {
+                    if !in_kernel(dx, dy) {
+                        continue;
+                    }
+
+                    let sx = x as i32 + dx;
+                    let sy = y as i32 + dy;
+
+                    if sx >= 0 && sx < w as i32 && sy >= 0 && sy < h as i32 {
+                        let idx = (sy as usize) * w + (sx as usize);
+                        value = if dilate {
+                            value.max(alpha_data[idx])
+                        } else {
+                            value.min(alpha_data[idx])
+                        };
+                    }
+                }
+            }
+
+            result[y * w + x] = value;
+        }
+    }
+
+    result
+}
+
+/// Remove isolated single-pixel specks and holes from an alpha mask
+///
+/// A 3×3 median filter: a lone opaque pixel in a transparent area, or a
+/// lone transparent pixel inside the sprite, is outvoted by its neighbors,
+/// while straight edges of solid regions stay put; only the outermost
+/// pixel of a sharp convex corner is trimmed.
+/// Cheap enough to run before heavier hole filling or component passes.
+/// Pixels past the image border repeat the nearest edge pixel.
+#[wasm_bindgen]
+pub fn despeckle_alpha(alpha_data: &[u8], width: u32, height: u32) -> Vec<u8> {
+    let w = width as usize;
+    let h = height as usize;
+    let mut result = vec![0u8; w * h];
+
+    for y in 0..h {
+        for x in 0..w {
+            let mut window = [0u8; 9];
+            let mut n = 0;
+            for dy in -1i32..=1 {
+                for dx in -1i32..=1 {
+                    let sx = (x as i32 + dx).clamp(0, w as i32 - 1) as usize;
+                    let sy = (y as i32 + dy).clamp(0, h as i32 - 1) as usize;
+                    window[n] = alpha_data[sy * w + sx];
+                    n += 1;
+                }
+            }
+            window.sort_unstable();
+            result[y * w + x] = window[4];
+        }
+    }
+
+    result
+}
+
+/// Bleed edge color into fully transparent neighbors
+///
+/// GPU bilinear filtering blends in the RGB of transparent texels, which
+/// is usually black and shows up as a dark fringe. Each iteration gives
+/// every still-unfilled transparent pixel the average RGB of its filled
+/// 8-neighbors, growing the color one pixel outward from the sprite.
+/// Alpha is left untouched, so the result composites exactly as before.
+///
+/// # Arguments
+///
+/// * `image_data` - RGBA pixel data (straight alpha)
+/// * `width` - Image width
+/// * `height` - Image height
+/// * `iterations` - How many pixels to extend color past the edge
+///
+/// # Returns
+///
+/// RGBA pixel data with transparent RGB filled near the sprite
+#[wasm_bindgen]
+pub fn extend_edges(
+    image_data: &[u8],
+    width: u32,
+    height: u32,
+    iterations: u32,
+) -> Vec<u8> {
+    let w = width as usize;
+    let h = height as usize;
+
+    let mut result = image_data[..w * h * 4].to_vec();
+    let mut filled: Vec<bool> = result.chunks_exact(4).map(|px| px[3] > 0).collect();
+
+    for _ in 0..iterations {
+        let mut next = filled.clone();
+        let mut changed = false;
+
+        for y in 0..h {
+            for x in 0..w {
+                let idx = y * w + x;
+                if filled[idx] {
+                    continue;
+                }
+
+                let mut sum = [0u32; 3];
+                let mut count = 0u32;
+                for sy in y.saturating_sub(1)..(y + 2).min(h) {
+                    for sx in x.saturating_sub(1)..(x + 2).min(w) {
+                        let nidx = sy * w + sx;
+                        if filled[nidx] {
+                            for c in 0..3 {
+                                sum[c] += result[nidx * 4 + c] as u32;
+                            }
+                            count += 1;
+                        }
+                    }
+                }
+
+                if count == 0 {
+                    continue;
+                }
+
+                for c in 0..3 {
+                    result[idx * 4 + c] = ((sum[c] + count / 2) / count) as u8;
+                }
+                next[idx] = true;
+                changed = true;
+            }
+        }
+
+        filled = next;
+        if !changed {
+            break;
+        }
+    }
+
+    result
+}
+
+/// Remap alpha through a levels adjustment
+///
+/// Alpha at or below `in_low` becomes `out_low`, at or above `in_high`
+/// becomes `out_high`, and values between are mapped linearly. Typical use
+/// after [`normalize_matte`] is clipping near-opaque body pixels to fully
+/// opaque (`in_high = 200, out_high = 255`). `out_low > out_high` inverts;
+/// `in_high <= in_low` makes a hard step.
+///
+/// # Arguments
+///
+/// * `alpha_data` - Alpha channel as u8 array
+/// * `in_low` - Input black point
+/// * `in_high` - Input white point
+/// * `out_low` - Output for alpha at or below `in_low`
+/// * `out_high` - Output for alpha at or above `in_high`
+#[wasm_bindgen]
+pub fn alpha_levels(
+    alpha_data: &[u8],
+    in_low: u8,
+    in_high: u8,
+    out_low: u8,
+    out_high: u8,
+) -> Vec<u8> {
+    // Precompute all 256 outputs, then map
+    let lut: Vec<u8> = (0..=255u8)
+        .map(|a| {
+            let t = if a <= in_low {
+                0.0
+            } else if a >= in_high {
+                1.0
+            } else {
+                (a - in_low) as f32 / (in_high - in_low) as f32
+            };
+            to_u8(out_low as f32 + t * (out_high as f32 - out_low as f32))
+        })
+        .collect();
+
+    alpha_data.iter().map(|&a| lut[a as usize]).collect()
+}
+
+/// Spread alpha over the full 0-255 range by histogram equalization
+///
+/// Each value maps to its position in the cumulative distribution, with
+/// the lowest value present going to 0 and the highest to 255, so
+/// mattes from sources with very different contrast behave alike under a
+/// fixed threshold. The mapping is monotonic: ordering between pixels is
+/// preserved (equal values stay equal). A uniform buffer is returned as is.
+///
+/// # Arguments
+///
+/// * `alpha_data` - Alpha channel as u8 array
+#[wasm_bindgen]
+pub fn equalize_alpha(alpha_data: &[u8]) -> Vec<u8> {
+    let mut histogram = [0usize; 256];
+    for &a in alpha_data {
+        histogram[a as usize] += 1;
+    }
+
+    // Cumulative counts, and the count at the lowest value present
+    let mut cdf = [0usize; 256];
+    let mut running = 0;
+    for (total, count) in cdf.iter_mut().zip(histogram) {
+        running += count;
+        *total = running;
+    }
+    let first = cdf.iter().copied().find(|&c| c > 0).unwrap_or(0);
+    let span = alpha_data.len() - first;
+    if span == 0 {
+        return alpha_data.to_vec();
+    }
+
+    let lut: Vec<u8> = cdf
+        .iter()
+        .map(|&c| to_u8(c.saturating_sub(first) as f32 / span as f32 * 255.0))
+        .collect();
+
+    alpha_data.iter().map(|&a| lut[a as usize]).collect()
+}
+
+/// Derive an alpha matte from brightness
+///
+/// For content that arrives as grayscale without alpha, where brightness
+/// implies the sprite. Rec. 709 luminance is mapped through a ramp: at or
+/// below `low` is transparent, at or above `high` is opaque, and linear in
+/// between. If `high <= low` the ramp becomes a hard step.
+///
+/// # Arguments
+///
+/// * `image_data` - RGBA pixel data (input alpha is ignored)
+/// * `width` - Image width
+/// * `height` - Image height
+/// * `low` - Luminance (0-255) below which pixels are transparent
+/// * `high` - Luminance (0-255) above which pixels are opaque
+///
+/// # Returns
+///
+/// RGBA pixel data with the original color and the derived alpha
+#[wasm_bindgen]
+pub fn matte_from_luminance(
+    image_data: &[u8],
+    width: u32,
+    height: u32,
+    low: u8,
+    high: u8,
+) -> Vec<u8> {
+    let size = width as usize * height as usize;
+    let mut result = image_data[..size * 4].to_vec();
+
+    for px in result.chunks_exact_mut(4) {
+        let luma = 0.2126 * px[0] as f32 + 0.7152 * px[1] as f32 + 0.0722 * px[2] as f32;
+
+        px[3] = if luma <= low as f32 {
+            0
+        } else if luma >= high as f32 {
+            255
+        } else {
+            to_u8((luma - low as f32) / (high - low) as f32 * 255.0)
+        };
+    }
+
+    result
+}
+
+/// Neutralize color spill from a keyed background
+///
+/// The spill channel is whichever of red, green or blue dominates
+/// `spill_color`. Wherever that channel exceeds the average of the other
+/// two, it is pulled toward that average by `strength`: 1 removes the
+/// excess entirely, 0 leaves the image unchanged. Pixels where the channel
+/// doesn't dominate, and alpha, are untouched.
+///
+/// # Arguments
+///
+/// * `image_data` - RGBA pixel data
+/// * `width` - Image width
+/// * `height` - Image height
+/// * `spill_color` - Packed `0xRRGGBBAA` key color, e.g. `0x00ff00ff`
+/// * `strength` - Fraction of the excess to remove (0.0-1.0)
+#[wasm_bindgen]
+pub fn despill(
+    image_data: &[u8],
+    width: u32,
+    height: u32,
+    spill_color: u32,
+    strength: f32,
+) -> Vec<u8> {
+    let size = width as usize * height as usize;
+    let mut result = image_data[..size * 4].to_vec();
+    let strength = strength.clamp(0.0, 1.0);
+
+    let key = spill_color.to_be_bytes();
+    let spill = (0..3).max_by_key(|&c| key[c]).unwrap_or(1);
+    let (a, b) = ((spill + 1) % 3, (spill + 2) % 3);
+
+    for px in result.chunks_exact_mut(4) {
+        let limit = (px[a] as f32 + px[b] as f32) / 2.0;
+        let value = px[spill] as f32;
+        if value > limit {
+            px[spill] = to_u8(value - strength * (value - limit));
+        }
+    }
+
+    result
+}
+
+/// Snap a soft matte's edge onto the image's color edges
+///
+/// A guided filter (He et al.) with the RGB image as guide: within every
+/// `(2 * radius + 1)²` window the output alpha is the linear function of
+/// the pixel's color that best fits the input alpha, and the per-window
+/// fits are averaged. Where the window straddles a color edge, alpha ends
+/// up following the color, so a blurry or misplaced matte boundary is
+/// pulled onto the object's actual edge. In flat-colored regions the fit
+/// degenerates to a box-smoothed input alpha.
+///
+/// # Arguments
+///
+/// * `image_data` - RGBA pixel data used as the guide (alpha ignored)
+/// * `alpha_data` - Matte to refine, one byte per pixel
+/// * `width` - Image width
+/// * `height` - Image height
+/// * `radius` - Window radius in pixels; should cover the matte's
+///   uncertainty band
+/// * `epsilon` - Regularization on the 0-1 color scale; smaller values
+///   follow color edges more tightly (1e-4 to 1e-2 is typical)
+///
+/// # Returns
+///
+/// Refined alpha, one byte per pixel
+#[wasm_bindgen]
+pub fn guided_matte_refine(
+    image_data: &[u8],
+    alpha_data: &[u8],
+    width: u32,
+    height: u32,
+    radius: u32,
+    epsilon: f32,
+) -> Vec<u8> {
+    let (w, h) = (width as usize, height as usize);
+    let size = w * h;
+    let eps = epsilon.max(1e-6);
+    let mean = |data: &[f32]| box_mean(data, w, h, radius as usize);
+    let product = |a: &[f32], b: &[f32]| -> Vec<f32> { a.iter().zip(b).map(|(x, y)| x * y).collect() };
+
+    let channel = |c: usize| -> Vec<f32> {
+        image_data[..size * 4]
+            .chunks_exact(4)
+            .map(|px| px[c] as f32 / 255.0)
+            .collect()
+    };
+    let guide = [channel(0), channel(1), channel(2)];
+    let input: Vec<f32> = alpha_data[..size].iter().map(|&a| a as f32 / 255.0).collect();
+
+    // Window statistics: guide means, guide/alpha covariance, and the
+    // guide's covariance matrix (upper triangle: rr, rg, rb, gg, gb, bb)
+    let mean_i = [mean(&guide[0]), mean(&guide[1]), mean(&guide[2])];
+    let mean_p = mean(&input);
+    let cov_ip: Vec<Vec<f32>> = (0..3)
+        .map(|c| {
+            let corr = mean(&product(&guide[c], &input));
+            (0..size).map(|k| corr[k] - mean_i[c][k] * mean_p[k]).collect()
+        })
+        .collect();
+    let var: Vec<Vec<f32>> = [(0, 0), (0, 1), (0, 2), (1, 1), (1, 2), (2, 2)]
+        .iter()
+        .map(|&(i, j)| {
+            let corr = mean(&product(&guide[i], &guide[j]));
+            (0..size).map(|k| corr[k] - mean_i[i][k] * mean_i[j][k]).collect()
+        })
+        .collect();
+
+    // Per-window fit alpha ≈ a · color + b, solving (Σ + εI) a = cov(I, p)
+    let mut coef = vec![vec![0.0f32; size]; 4];
+    for k in 0..size {
+        let (rr, rg, rb) = (var[0][k] + eps, var[1][k], var[2][k]);
+        let (gg, gb, bb) = (var[3][k] + eps, var[4][k], var[5][k] + eps);
+
+        // Symmetric 3x3 inverse from cofactors
+        let c00 = gg * bb - gb * gb;
+        let c01 = gb * rb - rg * bb;
+        let c02 = rg * gb - gg * rb;
+        let c11 = rr * bb - rb * rb;
+        let c12 = rb * rg - rr * gb;
+        let c22 = rr * gg - rg * rg;
+        let det = rr * c00 + rg * c01 + rb * c02;
+
+        let v = [cov_ip[0][k], cov_ip[1][k], cov_ip[2][k]];
+        let a = [
+            (c00 * v[0] + c01 * v[1] + c02 * v[2]) / det,
+            (c01 * v[0] + c11 * v[1] + c12 * v[2]) / det,
+            (c02 * v[0] + c12 * v[1] + c22 * v[2]) / det,
+        ];
+        coef[0][k] = a[0];
+        coef[1][k] = a[1];
+        coef[2][k] = a[2];
+        coef[3][k] = mean_p[k] - (0..3).map(|c| a[c] * mean_i[c][k]).sum::<f32>();
+    }
+
+    // Average the fits of every window covering each pixel, then apply
+    let coef: Vec<Vec<f32>> = coef.iter().map(|c| mean(c)).collect();
+    (0..size)
+        .map(|k| {
+            let q = (0..3).map(|c| coef[c][k] * guide[c][k]).sum::<f32>() + coef[3][k];
+            to_u8(q * 255.0)
+        })
+        .collect()
+}
+
+/// Reconstruct anti-aliased edges from a hard binary mask
+///
+/// Computes a short-range signed distance to the silhouette, averages it over
+/// each pixel's 3×3 neighborhood and maps the result to coverage as
+/// `clamp(0.5 - distance)`. The distance is linear across straight edges,
+/// so axis-aligned edges and interiors come back unchanged; only staircase
+/// steps, where the field bends, get intermediate alpha.
+///
+/// # Arguments
+///
+/// * `alpha_data` - Binary alpha (0 or 255; thresholded at [`OPAQUE_THRESHOLD`])
+/// * `width` - Image width
+/// * `height` - Image height
+///
+/// # Returns
+///
+/// Anti-aliased alpha channel
+#[wasm_bindgen]
+pub fn antialias_binary_mask(
+    alpha_data: &[u8],
+    width: u32,
+    height: u32,
+) -> Vec<u8> {
+    let w = width as usize;
+    let h = height as usize;
+
+    // Signed distance from the silhouette to each pixel center. Clamping
+    // to a few pixels keeps uniform masks (no seeds) finite.
+    let distances = seed_distances(alpha_data, w, h, &SdfOptions::default());
+    let signed: Vec<f32> = distances
+        .iter()
+        .zip(alpha_data)
+        .map(|(&d, &a)| {
+            let dist = d.min(4.0) + 0.5;
+            if a >= OPAQUE_THRESHOLD { -dist } else { dist }
+        })
+        .collect();
+
+    let mut result = vec![0u8; w * h];
+    for y in 0..h {
+        for x in 0..w {
+            let mut sum = 0.0f32;
+            let mut count = 0.0f32;
+            for sy in y.saturating_sub(1)..(y + 2).min(h) {
+                for sx in x.saturating_sub(1)..(x + 2).min(w) {
+                    sum += signed[sy * w + sx];
+                    count += 1.0;
+                }
+            }
+
+            let coverage = (0.5 - sum / count).clamp(0.0, 1.0);
+            result[y * w + x] = to_u8(coverage * 255.0);
+        }
+    }
+
+    result
+}
+
+/// Round the convex corners of a mask
+///
+/// A morphological opening in distance space: the silhouette is eroded by
+/// `radius` (keeping pixels more than `radius` from the edge) and the
+/// result dilated back by the same distance. Straight edges and concave
+/// corners return exactly where they were, while every convex corner
+/// comes back as an arc of `radius`. Features thinner than `2 * radius`
+/// vanish. Kept pixels keep their original alpha, so soft edges along the
+/// straight sections survive.
+///
+/// # Arguments
+///
+/// * `alpha_data` - Alpha channel (thresholded at [`OPAQUE_THRESHOLD`])
+/// * `width` - Image width
+/// * `height` - Image height
+/// * `radius` - Corner radius in pixels; 0 or less returns the mask unchanged
+///
+/// # Returns
+///
+/// Alpha channel with rounded corners
+#[wasm_bindgen]
+pub fn round_corners(
+    alpha_data: &[u8],
+    width: u32,
+    height: u32,
+    radius: f32,
+) -> Vec<u8> {
+    let w = width as usize;
+    let h = height as usize;
+    if radius <= 0.0 {
+        return alpha_data[..w * h].to_vec();
+    }
+
+    // Distances are to the nearest edge pixel center, half a pixel short
+    // of the silhouette itself
+    let options = SdfOptions::default();
+    let distances = seed_distances(alpha_data, w, h, &options);
+    let eroded: Vec<u8> = distances
+        .iter()
+        .zip(alpha_data)
+        .map(|(&d, &a)| if a >= OPAQUE_THRESHOLD && d + 0.5 > radius { 255 } else { 0 })
+        .collect();
+
+    let grown = seed_distances(&eroded, w, h, &options);
+    alpha_data
+        .iter()
+        .zip(eroded.iter().zip(&grown))
+        .map(|(&a, (&core, &d))| if core == 255 || d + 0.5 <= radius { a } else { 0 })
+        .collect()
+}
+
+/// Feather only the outside of a mask's edge
+///
+/// Unlike the symmetric blur of [`normalize_matte`], the silhouette itself
+/// stays hard: every pixel at or above [`OPAQUE_THRESHOLD`] becomes fully
+/// opaque, and alpha ramps linearly from 255 at the edge down to 0 at
+/// `feather_px` outside it, measured with the SDF.
+///
+/// # Arguments
+///
+/// * `alpha_data` - Alpha channel
+/// * `width` - Image width
+/// * `height` - Image height
+/// * `feather_px` - Width of the outer ramp in pixels; 0 or less gives a
+///   hard binary mask
+///
+/// # Returns
+///
+/// Alpha channel with an outer feather
+#[wasm_bindgen]
+pub fn feather_outer(
+    alpha_data: &[u8],
+    width: u32,
+    height: u32,
+    feather_px: f32,
+) -> Vec<u8> {
+    let w = width as usize;
+    let h = height as usize;
+    let distances = seed_distances(alpha_data, w, h, &SdfOptions::default());
+
+    distances
+        .iter()
+        .zip(alpha_data)
+        .map(|(&d, &a)| {
+            if a >= OPAQUE_THRESHOLD {
+                255
+            } else if feather_px <= 0.0 {
+                0
+            } else {
+                // Outside pixel center to the silhouette
+                let dist = d + 0.5;
+                to_u8((1.0 - dist / feather_px) * 255.0)
+            }
+        })
+        .collect()
+}
+
+/// Generate a trimap for matting refinement
+///
+/// Pixels that stay fully opaque after eroding by `erode_radius` are
+/// definite foreground (255); pixels still fully transparent after dilating
+/// by `dilate_radius` are definite background (0). Everything in between,
+/// including any partially transparent pixel, is the unknown band (128)
+/// for a downstream matting pass to resolve.
+///
+/// # Arguments
+///
+/// * `alpha_data` - Alpha channel as u8 array
+/// * `width` - Image width
+/// * `height` - Image height
+/// * `erode_radius` - Width of the unknown band inside the silhouette
+/// * `dilate_radius` - Width of the unknown band outside the silhouette
+#[wasm_bindgen]
+pub fn generate_trimap(
+    alpha_data: &[u8],
+    width: u32,
+    height: u32,
+    erode_radius: u32,
+    dilate_radius: u32,
+) -> Vec<u8> {
+    let eroded = erode_alpha(alpha_data, width, height, erode_radius);
+    let dilated = dilate_alpha(alpha_data, width, height, dilate_radius);
+
+    eroded
+        .iter()
+        .zip(&dilated)
+        .map(|(&fg, &bg)| match (fg, bg) {
+            (255, _) => 255,
+            (_, 0) => 0,
+            _ => 128,
+        })
+        .collect()
+}
+
+/// Conservative collision mask covering every pixel the shape touches
+///
+/// Any nonzero alpha counts as partial coverage, and since the covered part
+/// of a pixel can lie anywhere inside it, the shape may come within half a
+/// pixel of each of that pixel's 8 neighbors. The exact rule is therefore:
+/// a pixel is marked 255 if it or any of its 8 neighbors has alpha > 0,
+/// otherwise 0. The result is a superset of any thresholded mask.
+///
+/// # Arguments
+///
+/// * `alpha_data` - Alpha channel as u8 array
+/// * `width` - Image width
+/// * `height` - Image height
+#[wasm_bindgen]
+pub fn conservative_mask(
+    alpha_data: &[u8],
+    width: u32,
+    height: u32,
+) -> Vec<u8> {
+    let w = width as usize;
+    let h = height as usize;
+
+    let mut result = vec![0u8; w * h];
+
+    for y in 0..h {
+        for x in 0..w {
+            let touched = (y.saturating_sub(1)..(y + 2).min(h)).any(|sy| {
+                (x.saturating_sub(1)..(x + 2).min(w)).any(|sx| alpha_data[sy * w + sx] > 0)
+            });
+
+            if touched {
+                result[y * w + x] = 255;
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::opaque_threshold;
+    use crate::test_util::disc;
+
+    #[test]
+    fn test_centroid_empty() {
+        let alpha = vec![0u8; 16];
+        let c = calculate_centroid(&alpha, 4, 4, 128);
+        assert_eq!(c.area, 0);
+        assert_eq!(c.x, 2.0);
+        assert_eq!(c.y, 2.0);
+    }
+
+    #[test]
+    fn test_centroid_filled() {
         let alpha = vec![255u8; 16];
         let c = calculate_centroid(&alpha, 4, 4, 128);
         assert_eq!(c.area, 16);
@@ -307,6 +1771,537 @@ mod tests {
         assert_eq!(c.y, 1.5);
     }
 
+    #[test]
+    fn test_uniform_fast_path() {
+        for value in [0u8, 255] {
+            let alpha = vec![value; 20];
+            let fast = calculate_centroid(&alpha, 5, 4, 128);
+            let full = accumulate_centroid(&alpha, 5, 4, 128, 255);
+            assert_eq!(fast.x, full.x);
+            assert_eq!(fast.y, full.y);
+            assert_eq!(fast.area, full.area);
+            assert_eq!(fast.bounds_width, full.bounds_width);
+            assert_eq!(fast.bounds_height, full.bounds_height);
+
+            // Matte fast path against the smoothing path it skips
+            let mut image: Vec<u8> = (0..20).flat_map(|_| [200, 100, 50, value]).collect();
+            let mut expected = image.clone();
+            let smoothed = smooth_alpha(&[value; 20], 5, 4, 0.5);
+            for i in 0..20 {
+                let a = smoothed[i] as f32 / 255.0;
+                for c in 0..3 {
+                    expected[i * 4 + c] = to_u8(expected[i * 4 + c] as f32 * a);
+                }
+                expected[i * 4 + 3] = smoothed[i];
+            }
+
+            let c = normalize_matte(&mut image, 5, 4, 0.5);
+            assert_eq!(image, expected);
+            assert_eq!(c.area, full.area);
+        }
+    }
+
+    #[test]
+    fn test_centroids_multi() {
+        // 3x3 blob at top-left, 2x2 blob at bottom-right, 1px speck
+        let (w, h) = (10usize, 10usize);
+        let mut alpha = vec![0u8; w * h];
+        for y in 1..4 {
+            for x in 1..4 {
+                alpha[y * w + x] = 255;
+            }
+        }
+        for y in 6..8 {
+            for x in 6..8 {
+                alpha[y * w + x] = 255;
+            }
+        }
+        alpha[9 * w] = 255;
+
+        let centroids = calculate_centroids_multi(&alpha, w as u32, h as u32, 128, 2);
+        assert_eq!(centroids.len(), 2);
+
+        assert_eq!(centroids[0].area, 9);
+        assert_eq!((centroids[0].x, centroids[0].y), (2.0, 2.0));
+        assert_eq!(centroids[0].bounds_width, 3);
+
+        assert_eq!(centroids[1].area, 4);
+        assert_eq!((centroids[1].x, centroids[1].y), (6.5, 6.5));
+        assert_eq!((centroids[1].bounds_x, centroids[1].bounds_y), (6, 6));
+    }
+
+    #[test]
+    fn test_normalize_into_keeps_source() {
+        let (w, h) = (12usize, 10usize);
+        let src: Vec<u8> = (0..w * h)
+            .flat_map(|i| {
+                let a = if i % w < 6 { 255 } else { 0 };
+                [200, 120, 40, a]
+            })
+            .collect();
+        let original = src.clone();
+
+        let mut expected = src.clone();
+        let expected_centroid = normalize_matte(&mut expected, w as u32, h as u32, 0.5);
+
+        let mut dst = vec![0u8; src.len()];
+        let centroid = normalize_matte_into(&src, &mut dst, w as u32, h as u32, 0.5).unwrap();
+
+        assert_eq!(src, original);
+        assert_eq!(dst, expected);
+        assert_eq!(centroid.x, expected_centroid.x);
+        assert_eq!(centroid.area, expected_centroid.area);
+
+        assert!(normalize_matte_into(&src, &mut dst[4..], w as u32, h as u32, 0.5).is_err());
+    }
+
+    #[test]
+    fn test_conservative_mask() {
+        // Anti-aliased diagonal edge: full below the diagonal, partial on it
+        let n = 8usize;
+        let mut alpha = vec![0u8; n * n];
+        for y in 0..n {
+            for x in 0..n {
+                alpha[y * n + x] = match x.cmp(&y) {
+                    std::cmp::Ordering::Less => 255,
+                    std::cmp::Ordering::Equal => 60,
+                    std::cmp::Ordering::Greater => 0,
+                };
+            }
+        }
+
+        let mask = conservative_mask(&alpha, n as u32, n as u32);
+        for y in 0..n {
+            for x in 0..n {
+                let idx = y * n + x;
+                // Partially covered diagonal is dropped by a hard threshold
+                if x == y {
+                    assert!(alpha[idx] < 128);
+                }
+                // ...but kept, along with every pixel neighboring it
+                let expected = if x <= y + 2 { 255 } else { 0 };
+                assert_eq!(mask[idx], expected, "pixel ({}, {})", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn test_straight_matte_keeps_color() {
+        // Opaque orange column 0, transparent elsewhere on a 6x1 strip
+        let mut image = vec![0u8; 6 * 4];
+        image[0..4].copy_from_slice(&[200, 100, 50, 255]);
+        image[4..8].copy_from_slice(&[200, 100, 50, 255]);
+        image[8..12].copy_from_slice(&[200, 100, 50, 20]);
+        let mut premultiplied = image.clone();
+
+        normalize_matte_straight(&mut image, 6, 1, 1.0);
+        normalize_matte(&mut premultiplied, 6, 1, 1.0);
+
+        // The faint edge pixel keeps its straight color
+        let edge = &image[8..12];
+        assert!(edge[3] > 0 && edge[3] < 255);
+        assert_eq!(&edge[..3], &[200, 100, 50]);
+
+        // The premultiplied path can't recover it by unpremultiplying
+        let lossy = &premultiplied[8..12];
+        let recovered = (lossy[2] as f32 * 255.0 / lossy[3] as f32).round() as u8;
+        assert_eq!(lossy[3], edge[3]);
+        assert_ne!(recovered, 50);
+    }
+
+    #[test]
+    fn test_centroid_banded() {
+        // 4x4 opaque square at (3, 3) with a one-pixel faint halo
+        let mut alpha = vec![0u8; 100];
+        for y in 2..8 {
+            for x in 2..8 {
+                alpha[y * 10 + x] = 15;
+            }
+        }
+        for y in 3..7 {
+            for x in 3..7 {
+                alpha[y * 10 + x] = 255;
+            }
+        }
+
+        let tight = calculate_centroid_banded(&alpha, 10, 10, 64, 255);
+        assert_eq!(tight.area, 16);
+        assert_eq!((tight.bounds_x, tight.bounds_width), (3, 4));
+
+        let halo = calculate_centroid_banded(&alpha, 10, 10, 8, 255);
+        assert_eq!(halo.area, 36);
+        assert_eq!((halo.bounds_x, halo.bounds_width), (2, 6));
+
+        // Upper bound isolates the halo ring itself
+        let ring = calculate_centroid_banded(&alpha, 10, 10, 8, 64);
+        assert_eq!(ring.area, 20);
+    }
+
+    #[test]
+    fn test_centroid_single_pixel() {
+        let mut alpha = vec![0u8; 25];
+        alpha[3 * 5 + 1] = 255;
+
+        let c = calculate_centroid(&alpha, 5, 5, 128);
+        assert_eq!(c.area, 1);
+        assert_eq!((c.x, c.y), (1.0, 3.0));
+        assert_eq!((c.bounds_x, c.bounds_y), (1, 3));
+        assert_eq!(c.bounds_width, 1);
+        assert_eq!(c.bounds_height, 1);
+
+        assert_eq!(bounds_span(4, 4), 1);
+        assert_eq!(bounds_span(5, 4), 0);
+    }
+
+    #[test]
+    fn test_convex_hull_l_shape() {
+        // L: vertical bar x 2..4, y 2..10 plus foot x 2..10, y 8..10
+        let mut alpha = vec![0u8; 144];
+        for y in 2..10 {
+            for x in 2..10 {
+                if x < 4 || y >= 8 {
+                    alpha[y * 12 + x] = 255;
+                }
+            }
+        }
+
+        let hull = convex_hull(&alpha, 12, 12, 128);
+        let vertices: Vec<(f32, f32)> = hull.chunks_exact(2).map(|p| (p[0], p[1])).collect();
+        assert_eq!(vertices.len(), 5, "{:?}", vertices);
+        for corner in [(2.0, 2.0), (3.0, 2.0), (2.0, 9.0), (9.0, 9.0), (9.0, 8.0)] {
+            assert!(vertices.contains(&corner), "missing {:?}", corner);
+        }
+
+        // Every opaque pixel lies inside or on the hull
+        let n = vertices.len();
+        let side = |i: usize, x: f32, y: f32| {
+            let (ax, ay) = vertices[i];
+            let (bx, by) = vertices[(i + 1) % n];
+            (bx - ax) * (y - ay) - (by - ay) * (x - ax)
+        };
+        let sign = side(0, 5.0, 5.0).signum();
+        for y in 0..12 {
+            for x in 0..12 {
+                if alpha[y * 12 + x] < 128 {
+                    continue;
+                }
+                for i in 0..n {
+                    let c = side(i, x as f32, y as f32);
+                    assert!(c * sign >= 0.0, "pixel ({}, {}) outside hull", x, y);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_suggest_crop() {
+        // 10x10 square at (10, 10) in a 40x40 frame
+        let size = 40usize;
+        let mut alpha = vec![0u8; size * size];
+        for y in 10..20 {
+            alpha[y * size + 10..y * size + 20].fill(255);
+        }
+
+        // 20% margin: 2px on each side of the tight bounds
+        let crop = suggest_crop(&alpha, size as u32, size as u32, 128, 0.2);
+        assert_eq!(crop, Bounds { x: 8, y: 8, width: 14, height: 14 });
+
+        // A huge margin is clamped to the image
+        let crop = suggest_crop(&alpha, size as u32, size as u32, 128, 5.0);
+        assert_eq!(crop, Bounds { x: 0, y: 0, width: 40, height: 40 });
+    }
+
+    #[test]
+    fn test_quantize_centroid() {
+        // 10x10 block centered on (14.5, 14.5), then one stray pixel that
+        // nudges the raw centroid by about 0.1px
+        let size = 32usize;
+        let mut a = vec![0u8; size * size];
+        for y in 10..20 {
+            a[y * size + 10..y * size + 20].fill(255);
+        }
+        let mut b = a.clone();
+        b[14 * size + 25] = 255;
+
+        let ca = calculate_centroid(&a, size as u32, size as u32, 128);
+        let cb = calculate_centroid(&b, size as u32, size as u32, 128);
+        assert!((cb.x - ca.x - 0.1).abs() < 0.01, "{} vs {}", ca.x, cb.x);
+
+        let qa = quantize_centroid(&ca, 0.5);
+        let qb = quantize_centroid(&cb, 0.5);
+        assert_eq!((qa.x, qa.y), (14.5, 14.5));
+        assert_eq!((qb.x, qb.y), (qa.x, qa.y));
+        assert_eq!(qb.area, cb.area);
+    }
+
+    #[test]
+    fn test_crop_and_resize() {
+        // Opaque red 8x8 square at (12, 12) in a 32x32 frame
+        let size = 32usize;
+        let mut image = vec![0u8; size * size * 4];
+        for y in 12..20 {
+            for x in 12..20 {
+                image[(y * size + x) * 4..][..4].copy_from_slice(&[255, 0, 0, 255]);
+            }
+        }
+
+        // 4px margin: the 16x16 crop scales 4x, sprite spans 16..48
+        let out = crop_and_resize(&image, size as u32, size as u32, 128, 64, 64, 4);
+        assert_eq!(out.len(), 64 * 64 * 4);
+        let px = |x: usize, y: usize| &out[(y * 64 + x) * 4..][..4];
+
+        assert_eq!(px(32, 32), [255, 0, 0, 255]);
+        assert_eq!(px(20, 44), [255, 0, 0, 255]);
+        assert_eq!(px(12, 32)[3], 0);
+        assert_eq!(px(52, 32)[3], 0);
+        assert_eq!(px(32, 12)[3], 0);
+
+        // Centered: coverage is symmetric about the middle
+        for x in 0..32 {
+            assert_eq!(px(x, 32)[3], px(63 - x, 32)[3], "x = {}", x);
+        }
+
+        // Wide output letterboxes the square crop horizontally
+        let wide = crop_and_resize(&image, size as u32, size as u32, 128, 64, 32, 0);
+        let alpha = |x: usize, y: usize| wide[(y * 64 + x) * 4 + 3];
+        assert_eq!(alpha(32, 16), 255);
+        assert_eq!(alpha(8, 16), 0);
+        assert_eq!(alpha(20, 16), 255);
+    }
+
+    #[test]
+    fn test_coverage_area() {
+        // Left half at alpha 128, right half empty
+        let alpha: Vec<u8> = (0..64).map(|i| if i % 8 < 4 { 128 } else { 0 }).collect();
+
+        let coverage = coverage_area(&alpha);
+        assert!((coverage / 64.0 - 0.25).abs() < 0.01, "coverage {}", coverage);
+
+        // The thresholded area counts the same pixels as fully covered
+        let c = calculate_centroid(&alpha, 8, 8, 128);
+        assert_eq!(c.area, 32);
+    }
+
+    #[test]
+    fn test_extend_edges() {
+        // Opaque red at the left of a transparent black row
+        let mut data = vec![0u8; 5 * 4];
+        data[..4].copy_from_slice(&[255, 0, 0, 255]);
+
+        let one = extend_edges(&data, 5, 1, 1);
+        assert_eq!(one[4..8], [255, 0, 0, 0]);
+        assert_eq!(one[8..12], [0, 0, 0, 0]);
+
+        let all = extend_edges(&data, 5, 1, 10);
+        assert!(all.chunks_exact(4).all(|px| px[..3] == [255, 0, 0]));
+        assert!(all.chunks_exact(4).skip(1).all(|px| px[3] == 0));
+    }
+
+    #[test]
+    fn test_alpha_levels() {
+        let alpha = [0u8, 10, 100, 200, 230, 255];
+
+        // Clip near-opaque to opaque, keep the low end
+        let clipped = alpha_levels(&alpha, 0, 200, 0, 255);
+        assert_eq!(clipped, [0, 13, 128, 255, 255, 255]);
+
+        // Drop faint halos, compress into a narrower output range
+        let remapped = alpha_levels(&alpha, 10, 255, 20, 220);
+        assert_eq!(remapped[..2], [20, 20]);
+        assert_eq!(remapped[5], 220);
+
+        // Inverted output
+        assert_eq!(alpha_levels(&[0, 255], 0, 255, 255, 0), [255, 0]);
+    }
+
+    #[test]
+    fn test_centroid_exact() {
+        // 2048x2048 mask with an irregular pattern of opaque pixels
+        let (w, h) = (2048usize, 2048usize);
+        let alpha: Vec<u8> = (0..w * h)
+            .map(|i| {
+                let (x, y) = (i % w, i / w);
+                if (x * 7 + y * 13) % 29 < 11 && x > 100 + y / 3 { 255 } else { 0 }
+            })
+            .collect();
+
+        let exact = calculate_centroid_exact(&alpha, w as u32, h as u32, 128);
+        let float = calculate_centroid(&alpha, w as u32, h as u32, 128);
+
+        assert_eq!(exact.x, float.x.round());
+        assert_eq!(exact.y, float.y.round());
+        assert_eq!(exact.area, float.area);
+        assert_eq!(
+            (exact.bounds_x, exact.bounds_y, exact.bounds_width, exact.bounds_height),
+            (float.bounds_x, float.bounds_y, float.bounds_width, float.bounds_height)
+        );
+
+        // Deterministic across runs
+        let again = calculate_centroid_exact(&alpha, w as u32, h as u32, 128);
+        assert_eq!((again.x, again.y, again.area), (exact.x, exact.y, exact.area));
+
+        // Exact half-pixel means round up: pixels at x = 0 and x = 1
+        let pair = calculate_centroid_exact(&[255, 255, 0, 0], 4, 1, 128);
+        assert_eq!(pair.x, 1.0);
+    }
+
+    #[test]
+    fn test_principal_axis() {
+        let bar = |horizontal: bool| -> Vec<u8> {
+            (0..32 * 32)
+                .map(|i| {
+                    let (x, y) = (i % 32, i / 32);
+                    let (along, across) = if horizontal { (x, y) } else { (y, x) };
+                    if (4..28).contains(&along) && (14..18).contains(&across) { 255 } else { 0 }
+                })
+                .collect()
+        };
+
+        let wide = principal_axis(&bar(true), 32, 32, 128);
+        let tall = principal_axis(&bar(false), 32, 32, 128);
+        assert!(wide.abs() < 1e-3, "{}", wide);
+        assert!((tall - std::f32::consts::FRAC_PI_2).abs() < 1e-3, "{}", tall);
+
+        // Diagonal stroke from top-left to bottom-right points down-right
+        let diagonal: Vec<u8> = (0..32 * 32usize)
+            .map(|i| if (i % 32).abs_diff(i / 32) <= 1 { 255 } else { 0 })
+            .collect();
+        let angle = principal_axis(&diagonal, 32, 32, 128);
+        assert!((angle - std::f32::consts::FRAC_PI_4).abs() < 1e-3, "{}", angle);
+
+        assert_eq!(principal_axis(&[0; 16], 4, 4, 128), 0.0);
+    }
+
+    #[test]
+    fn test_equalize_alpha() {
+        // Low contrast: every value in 100-150, evenly used
+        let alpha: Vec<u8> = (0..510).map(|i| 100 + (i % 51) as u8).collect();
+        let equalized = equalize_alpha(&alpha);
+
+        assert_eq!(equalized.iter().min(), Some(&0));
+        assert_eq!(equalized.iter().max(), Some(&255));
+
+        // Ordering is preserved: the first 51 pixels hold 100..=150 in order
+        assert!(equalized[..51].windows(2).all(|pair| pair[1] > pair[0]));
+        assert!(alpha.iter().zip(&equalized).all(|(&a, &e)| e == equalized[a as usize - 100]));
+
+        assert_eq!(equalize_alpha(&[90; 8]), [90; 8]);
+        assert!(equalize_alpha(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_despill() {
+        let data = [100, 200, 120, 128, 200, 100, 60, 255];
+
+        // Excess green pulled to the red/blue average (110)
+        let full = despill(&data, 2, 1, 0x00ff_00ff, 1.0);
+        assert_eq!(full[..4], [100, 110, 120, 128]);
+        let half = despill(&data, 2, 1, 0x00ff_00ff, 0.5);
+        assert_eq!(half[1], 155);
+
+        // Pixels without dominant green are left alone
+        assert_eq!(full[4..], data[4..]);
+
+        // A blue key targets blue instead
+        let blue = despill(&[10, 20, 90, 255], 1, 1, 0x0000_ffff, 1.0);
+        assert_eq!(blue, [10, 20, 15, 255]);
+    }
+
+    #[test]
+    fn test_guided_matte_refine() {
+        // Red object (x < 16) on a blue background, with a soft matte whose
+        // edge ramps over x = 13..25, centered 3px too far right
+        let (w, h) = (32usize, 8usize);
+        let image: Vec<u8> = (0..w * h)
+            .flat_map(|i| if i % w < 16 { [220, 40, 30, 255] } else { [20, 60, 200, 255] })
+            .collect();
+        let alpha: Vec<u8> = (0..w * h)
+            .map(|i| to_u8((25.0 - (i % w) as f32) / 12.0 * 255.0))
+            .collect();
+
+        let refined = guided_matte_refine(&image, &alpha, w as u32, h as u32, 4, 1e-4);
+
+        // Where each matte crosses 50% along the middle row
+        let crossing = |matte: &[u8]| {
+            let row = &matte[4 * w..5 * w];
+            (0..w - 1).find(|&x| row[x] >= 128 && row[x + 1] < 128).unwrap() as f32 + 0.5
+        };
+        let before = crossing(&alpha);
+        let after = crossing(&refined);
+        assert!((after - 15.5).abs() < (before - 15.5).abs(), "{} -> {}", before, after);
+
+        // Sharper too: the step across the color edge grows
+        let step = |matte: &[u8]| matte[4 * w + 15] as i32 - matte[4 * w + 16] as i32;
+        assert!(step(&refined) > step(&alpha));
+    }
+
+    #[test]
+    fn test_matte_from_luminance() {
+        // Gray ramp 0..=255, one value per pixel
+        let data: Vec<u8> = (0..=255u8).flat_map(|v| [v, v, v, 0]).collect();
+        let matte = matte_from_luminance(&data, 256, 1, 64, 192);
+
+        for (v, px) in matte.chunks_exact(4).enumerate() {
+            let expected = ((v as f32 - 64.0) / 128.0 * 255.0).clamp(0.0, 255.0);
+            assert!(
+                (px[3] as f32 - expected).abs() <= 1.0,
+                "luminance {}: alpha {}",
+                v,
+                px[3]
+            );
+            assert_eq!(px[0], v as u8);
+        }
+        assert_eq!(matte[64 * 4 + 3], 0);
+        assert_eq!(matte[128 * 4 + 3], 128);
+        assert_eq!(matte[192 * 4 + 3], 255);
+    }
+
+    #[test]
+    fn test_antialias_diagonal() {
+        let size = 16usize;
+
+        // Staircase diagonal: opaque where x < y
+        let diagonal: Vec<u8> = (0..size * size)
+            .map(|i| if i % size < i / size { 255 } else { 0 })
+            .collect();
+        let smoothed = antialias_binary_mask(&diagonal, size as u32, size as u32);
+
+        // Intermediate alpha appears on both sides of the staircase
+        for y in 4..12 {
+            let row = &smoothed[y * size..(y + 1) * size];
+            assert!(row[y - 1] < 255 && row[y - 1] > 128, "row {}: {:?}", y, row);
+            assert!(row[y] > 0 && row[y] < 128, "row {}: {:?}", y, row);
+            assert_eq!(row[y - 4], 255);
+            assert_eq!(row[y + 3], 0);
+        }
+
+        // A straight, pixel-aligned edge stays hard
+        let straight: Vec<u8> = (0..size * size)
+            .map(|i| if i % size < 8 { 255 } else { 0 })
+            .collect();
+        assert_eq!(antialias_binary_mask(&straight, size as u32, size as u32), straight);
+    }
+
+    #[test]
+    fn test_trimap_disc() {
+        // Disc of radius 10 centered on (20, 20)
+        let size = 40usize;
+        let alpha = disc((size, size), (20.0, 20.0), 10.0);
+        let trimap = generate_trimap(&alpha, size as u32, size as u32, 2, 3);
+
+        // Center row spans x = 10..=30: foreground shrinks by 2 on each
+        // side, the unknown band grows 3 beyond the silhouette
+        let row = &trimap[20 * size..21 * size];
+        for (x, &v) in row.iter().enumerate() {
+            let expected = match x {
+                12..=28 => 255,
+                7..=33 => 128,
+                _ => 0,
+            };
+            assert_eq!(v, expected, "pixel x = {}", x);
+        }
+    }
+
     #[test]
     fn test_dilate() {
         let mut alpha = vec![0u8; 9];
@@ -321,4 +2316,272 @@ mod tests {
         assert_eq!(result[5], 255); // Right
         assert_eq!(result[7], 255); // Bottom
     }
+
+    #[test]
+    fn test_dilate_xy_horizontal() {
+        let (w, h) = (11usize, 5usize);
+        let mut alpha = vec![0u8; w * h];
+        alpha[2 * w + 5] = 255;
+
+        let result = dilate_alpha_xy(&alpha, w as u32, h as u32, 3, 0);
+
+        // A 7-pixel horizontal line through the seed, nothing above or below
+        for y in 0..h {
+            for x in 0..w {
+                let expected = if y == 2 && (2..=8).contains(&x) { 255 } else { 0 };
+                assert_eq!(result[y * w + x], expected, "pixel ({}, {})", x, y);
+            }
+        }
+
+        // Equal radii reproduce the circular kernel
+        assert_eq!(
+            dilate_alpha_xy(&alpha, w as u32, h as u32, 2, 2),
+            dilate_alpha(&alpha, w as u32, h as u32, 2)
+        );
+    }
+
+    #[test]
+    fn test_opaque_threshold_agrees() {
+        use crate::sdf::{count_edge_seeds, generate_sdf};
+
+        // A 4x4 block right at the cutoff, in an 8x8 matte
+        let (w, h) = (8usize, 8usize);
+        for value in [OPAQUE_THRESHOLD - 1, OPAQUE_THRESHOLD] {
+            let alpha: Vec<u8> = (0..w * h)
+                .map(|i| if (2..6).contains(&(i % w)) && (2..6).contains(&(i / w)) { value } else { 0 })
+                .collect();
+            let mut image: Vec<u8> = alpha.iter().flat_map(|&a| [255, 255, 255, a]).collect();
+
+            let opaque = value >= OPAQUE_THRESHOLD;
+            let centroid = normalize_matte(&mut image, w as u32, h as u32, 0.0);
+            let edges = count_edge_seeds(&alpha, w as u32, h as u32, SdfOptions::default().threshold);
+            let sdf = generate_sdf(&alpha, w as u32, h as u32, 8.0);
+
+            assert_eq!(centroid.area > 0, opaque, "alpha {}", value);
+            assert_eq!(edges > 0, opaque, "alpha {}", value);
+            assert_eq!(sdf[3 * w + 3] < 128, opaque, "alpha {}", value);
+        }
+
+        // A per-call cutoff moves both together
+        let alpha = [127u8; 16];
+        let mut image: Vec<u8> = alpha.iter().flat_map(|&a| [255, 255, 255, a]).collect();
+        let centroid = normalize_matte_with_threshold(&mut image, 4, 4, 0.0, 127);
+        assert_eq!(centroid.area, 16);
+        assert_eq!(opaque_threshold(), 128);
+    }
+
+    #[test]
+    fn test_smooth_alpha_radius() {
+        assert_eq!(smooth_alpha_radius(0.0), 0);
+        assert_eq!(smooth_alpha_radius(-1.0), 0);
+        assert_eq!(smooth_alpha_radius(0.25), 1);
+        assert_eq!(smooth_alpha_radius(0.5), 1);
+        assert_eq!(smooth_alpha_radius(0.75), 2);
+        assert_eq!(smooth_alpha_radius(1.0), 2);
+
+        // The matte is smoothed with exactly that radius
+        let (w, h) = (16usize, 16usize);
+        let alpha: Vec<u8> = (0..w * h).map(|i| if i % w < 7 { 255 } else { 0 }).collect();
+        for softness in [0.2, 0.5, 0.6, 1.0] {
+            let blurred = box_blur(&alpha, w, h, smooth_alpha_radius(softness) as usize);
+            let expected: Vec<u8> = alpha
+                .iter()
+                .zip(&blurred)
+                .map(|(&a, &b)| to_u8(a as f32 * (1.0 - softness) + b as f32 * softness))
+                .collect();
+            assert_eq!(smooth_alpha(&alpha, w, h, softness), expected, "softness {}", softness);
+        }
+    }
+
+    #[test]
+    fn test_fill_ratio() {
+        let size = 32usize;
+        let square: Vec<u8> = (0..size * size)
+            .map(|i| if (8..20).contains(&(i % size)) && (10..22).contains(&(i / size)) { 255 } else { 0 })
+            .collect();
+        let solid = calculate_centroid(&square, size as u32, size as u32, 128);
+        assert!((solid.fill_ratio() - 1.0).abs() < 1e-6, "{}", solid.fill_ratio());
+
+        // A one-pixel diagonal fills 1/n of its n×n box
+        let line: Vec<u8> = (0..size * size)
+            .map(|i| if i % size == i / size { 255 } else { 0 })
+            .collect();
+        let thin = calculate_centroid(&line, size as u32, size as u32, 128);
+        assert!((thin.fill_ratio() - 1.0 / 32.0).abs() < 1e-6, "{}", thin.fill_ratio());
+
+        assert_eq!(calculate_centroid(&[0; 16], 4, 4, 128).fill_ratio(), 0.0);
+    }
+
+    #[test]
+    fn test_dilate_fractional() {
+        let (w, h) = (11usize, 11usize);
+        let mut alpha = vec![0u8; w * h];
+        alpha[5 * w + 5] = 255;
+
+        let one = dilate_alpha(&alpha, w as u32, h as u32, 1);
+        let two = dilate_alpha(&alpha, w as u32, h as u32, 2);
+        let half = dilate_alpha_f(&alpha, w as u32, h as u32, 1.5);
+
+        // Never outside the integer results, strictly between in total
+        for i in 0..w * h {
+            assert!(one[i] <= half[i] && half[i] <= two[i], "pixel {}", i);
+        }
+        let total = |mask: &[u8]| mask.iter().map(|&v| v as u32).sum::<u32>();
+        assert!(total(&one) < total(&half) && total(&half) < total(&two));
+        assert_eq!(half[5 * w + 7], 128);
+
+        assert_eq!(dilate_alpha_f(&alpha, w as u32, h as u32, 2.0), two);
+        assert_eq!(dilate_alpha_f(&alpha, w as u32, h as u32, -1.0), alpha);
+    }
+
+    #[test]
+    fn test_despeckle() {
+        let (w, h) = (9usize, 9usize);
+
+        // Lone opaque speck in a transparent field
+        let mut speck = vec![0u8; w * h];
+        speck[4 * w + 4] = 255;
+        assert!(despeckle_alpha(&speck, w as u32, h as u32).iter().all(|&a| a == 0));
+
+        // Lone hole in an opaque field, including one on the border
+        let mut holes = vec![255u8; w * h];
+        holes[4 * w + 4] = 0;
+        holes[3] = 0;
+        assert!(despeckle_alpha(&holes, w as u32, h as u32).iter().all(|&a| a == 255));
+
+        // A solid square keeps its edges, losing just the corner pixels
+        let square: Vec<u8> = (0..w * h)
+            .map(|i| if (2..7).contains(&(i % w)) && (2..7).contains(&(i / w)) { 255 } else { 0 })
+            .collect();
+        let mut rounded = square.clone();
+        for (x, y) in [(2, 2), (6, 2), (2, 6), (6, 6)] {
+            rounded[y * w + x] = 0;
+        }
+        assert_eq!(despeckle_alpha(&square, w as u32, h as u32), rounded);
+    }
+
+    #[test]
+    fn test_centroid_degenerate_sizes() {
+        let opaque = calculate_centroid(&[255], 1, 1, 128);
+        assert_eq!((opaque.x, opaque.y, opaque.area), (0.0, 0.0, 1));
+        assert_eq!((opaque.bounds_width, opaque.bounds_height), (1, 1));
+
+        let clear = calculate_centroid(&[0], 1, 1, 128);
+        assert_eq!(clear.area, 0);
+
+        let empty = calculate_centroid(&[], 0, 0, 128);
+        assert_eq!((empty.x, empty.y, empty.area), (0.0, 0.0, 0));
+        assert_eq!((empty.bounds_width, empty.bounds_height), (0, 0));
+        assert_eq!(calculate_centroid_exact(&[], 0, 0, 128).area, 0);
+    }
+
+    #[test]
+    fn test_centroid_approx() {
+        // Large off-center disc
+        let (w, h) = (400usize, 300usize);
+        let alpha = disc((w, h), (241.0, 127.0), 90.0);
+
+        let exact = calculate_centroid(&alpha, w as u32, h as u32, 128);
+        let approx = calculate_centroid_approx(&alpha, w as u32, h as u32, 128, 4);
+        assert!((approx.x - exact.x).abs() <= 2.0, "{} vs {}", approx.x, exact.x);
+        assert!((approx.y - exact.y).abs() <= 2.0, "{} vs {}", approx.y, exact.y);
+        assert!((approx.area as f32 / exact.area as f32 - 1.0).abs() < 0.05);
+        assert!(approx.bounds_x >= exact.bounds_x && approx.bounds_x - exact.bounds_x < 4);
+
+        // Deterministic, and stride 1 is exact
+        let again = calculate_centroid_approx(&alpha, w as u32, h as u32, 128, 4);
+        assert_eq!((again.x, again.y, again.area), (approx.x, approx.y, approx.area));
+        let full = calculate_centroid_approx(&alpha, w as u32, h as u32, 128, 1);
+        assert_eq!((full.x, full.y, full.area), (exact.x, exact.y, exact.area));
+    }
+
+    #[test]
+    fn test_round_corners() {
+        // 32x32 square at (8, 8) in a 48x48 mask
+        let size = 48usize;
+        let alpha: Vec<u8> = (0..size * size)
+            .map(|i| if (8..40).contains(&(i % size)) && (8..40).contains(&(i / size)) { 255 } else { 0 })
+            .collect();
+        let radius = 6.0f32;
+        let rounded = round_corners(&alpha, size as u32, size as u32, radius);
+
+        // Straight edges stay put, nothing grows outside the square
+        for i in 0..size * size {
+            assert!(rounded[i] <= alpha[i], "pixel {}", i);
+        }
+        for t in 14..34 {
+            assert_eq!(rounded[8 * size + t], 255, "top edge {}", t);
+            assert_eq!(rounded[t * size + 39], 255, "right edge {}", t);
+        }
+
+        // The top-left corner follows an arc of the radius about (14, 14)
+        for y in 8..14 {
+            for x in 8..14 {
+                let dx = x as f32 + 0.5 - (8.0 + radius);
+                let dy = y as f32 + 0.5 - (8.0 + radius);
+                let dist = (dx * dx + dy * dy).sqrt();
+                if dist < radius - 1.0 {
+                    assert_eq!(rounded[y * size + x], 255, "({}, {})", x, y);
+                } else if dist > radius + 1.0 {
+                    assert_eq!(rounded[y * size + x], 0, "({}, {})", x, y);
+                }
+            }
+        }
+        assert_eq!(rounded[8 * size + 8], 0);
+
+        assert_eq!(round_corners(&alpha, size as u32, size as u32, 0.0), alpha);
+    }
+
+    #[test]
+    fn test_feather_outer() {
+        // Soft-edged disc
+        let size = 40usize;
+        let alpha: Vec<u8> = (0..size * size)
+            .map(|i| {
+                let dx = (i % size) as f32 - 20.0;
+                let dy = (i / size) as f32 - 20.0;
+                to_u8((12.0 - (dx * dx + dy * dy).sqrt()).clamp(0.0, 1.0) * 255.0)
+            })
+            .collect();
+        let feathered = feather_outer(&alpha, size as u32, size as u32, 5.0);
+
+        for i in 0..size * size {
+            if alpha[i] >= OPAQUE_THRESHOLD {
+                assert_eq!(feathered[i], 255, "interior pixel {}", i);
+            }
+        }
+
+        // Along a row: hard inside, ramping down over ~5px outside, then 0
+        let row = &feathered[20 * size..21 * size];
+        assert_eq!(row[20..32], [255; 12]);
+        assert!(row[32] > row[33] && row[33] > row[34] && row[34] > 0, "{:?}", row);
+        assert_eq!(row[38], 0);
+        assert_eq!(row[0], 0);
+
+        let hard = feather_outer(&alpha, size as u32, size as u32, 0.0);
+        assert!(hard.iter().all(|&a| a == 0 || a == 255));
+    }
+
+    #[test]
+    fn test_morphological_gradient() {
+        let size = 40usize;
+        let radius = 10.0f32;
+        let dist = |i: usize| {
+            let dx = (i % size) as f32 - 20.0;
+            let dy = (i / size) as f32 - 20.0;
+            (dx * dx + dy * dy).sqrt()
+        };
+        let alpha: Vec<u8> = (0..size * size).map(|i| if dist(i) <= radius { 255 } else { 0 }).collect();
+
+        let band = morphological_gradient(&alpha, size as u32, size as u32, 2);
+        for (i, &v) in band.iter().enumerate() {
+            let d = dist(i);
+            if (d - radius).abs() > 3.5 {
+                assert_eq!(v, 0, "pixel {} at distance {}", i, d);
+            } else if (d - radius).abs() < 1.0 {
+                assert_eq!(v, 255, "pixel {} at distance {}", i, d);
+            }
+        }
+        assert!(band.iter().any(|&v| v > 0));
+    }
 }
@@ -0,0 +1,878 @@
+//! Panic-Safe Entry Points
+//!
+//! Enabled by the `safe` feature. Every exported function that touches
+//! caller data has a `*_safe` twin here that runs it under
+//! [`std::panic::catch_unwind`] and turns any internal panic (an
+//! out-of-bounds index from a short buffer, say) into [`Error::Panic`]
+//! instead of aborting the instance. Functions that already return
+//! `Result` keep their own errors; a panic becomes one more `Err`.
+//!
+//! Covered, by module:
+//!
+//! - `sdf`: `generate_sdf`, `generate_sdf_iso`, `generate_sdf_metric`,
+//!   `generate_sdf_opts`, `generate_sdf_schedule`, `generate_sdf_f16`,
+//!   `generate_sdf_pyramid`, `generate_sdf_into`, `generate_sdf_split`,
+//!   `crop_sdf`, `blend_sdf`, `combine_sdf`, `count_edge_seeds`, plus
+//!   `SdfGenerator::generate_safe`, `SdfGenerator::generate_warmstart_safe`
+//!   and `BatchJob::step_safe`
+//! - `mipmap`: `max_mipmap_levels`, `generate_mipmaps`,
+//!   `generate_mipmaps_full`, `generate_mipmaps_opts`,
+//!   `generate_mipmaps_region`, `generate_mipmaps_custom`,
+//!   `generate_mipmaps_u16`, `generate_mipmaps_stepped`, `downsample_half`,
+//!   `select_mipmap_level`, plus `MipmapStepper::next_level_safe`
+//! - `normalize`: every exported function, from `calculate_centroid`
+//!   through `conservative_mask`
+//! - `filter`: `gaussian_blur_rgba`, `unsharp_mask`, `bloom_mask`
+//! - `effects`: `sdf_stroke`, `sdf_isolines`, `dashed_outline`,
+//!   `sdf_stroke_adaptive`, `inset_outline`, `sdf_ao`, `sdf_thickness`,
+//!   `sdf_to_depth`
+//! - `color`: `srgb_to_linear_slice`, `linear_to_srgb_slice`,
+//!   `to_grayscale`, `region_average_colors`
+//! - `mask`: `rle_encode`, `rle_decode`, `pack_sdf_4bit`,
+//!   `unpack_sdf_4bit`, `diff_mask`, `ssim`
+//! - `hash`: `hash_buffer`
+//! - `sample`: `sample_bilinear_rgba`
+//! - `frame`: `generate_sdf_frame`, `generate_mipmaps_frame`,
+//!   `calculate_centroid_frame`, `generate_sdf_channel`,
+//!   `calculate_centroid_channel`, `fill_rgba`, `clear_rgba`, `slice_grid`
+//! - `composite`: `poisson_blend`, `crossfade`
+//! - crate root: `estimate_cost`
+//!
+//! Not wrapped: the build-info getters (`init`, `version`,
+//! `health_check`, `build_features`, `opaque_threshold`), which take no
+//! input, and accessors and constructors on the exported classes.
+//!
+//! Functions that write through a `&mut` buffer may leave it partially
+//! modified when a panic interrupts them.
+//!
+//! Catching requires unwinding: this works natively, but a
+//! `wasm32-unknown-unknown` build only unwinds when compiled with
+//! `-C panic=unwind` and WebAssembly exception handling. Without that,
+//! panics still trap. The commonest cause, a pixel buffer that doesn't
+//! match `width` × `height`, is therefore checked up front: every wrapped
+//! function either validates its buffers itself or has its wrapper do so,
+//! returning [`Error::LengthMismatch`] on any target.
+
+use std::panic::{catch_unwind, AssertUnwindSafe, UnwindSafe};
+
+use wasm_bindgen::prelude::*;
+
+use crate::error::{check_len, Error};
+use crate::frame::Frame;
+use crate::mipmap::{MipmapLevel, MipmapLevel16, MipmapOptions, MipmapStepper};
+use crate::normalize::{Bounds, Centroid};
+use crate::sdf::{BatchJob, BoolOp, DistanceMetric, SdfGenerator, SdfOptions, SplitSdf};
+use crate::{color, composite, effects, filter, frame, hash, mask, mipmap, normalize, sample, sdf};
+
+/// Run `f`, converting a panic into [`Error::Panic`]
+pub fn catch_panic<T>(f: impl FnOnce() -> T + UnwindSafe) -> Result<T, Error> {
+    catch_unwind(f).map_err(|payload| {
+        let message = if let Some(s) = payload.downcast_ref::<&str>() {
+            s.to_string()
+        } else if let Some(s) = payload.downcast_ref::<String>() {
+            s.clone()
+        } else {
+            "unknown panic".to_string()
+        };
+        Error::Panic { message }
+    })
+}
+
+/// Declare `name = module::target(args) -> Ret;` wrappers returning `Result<Ret, Error>`
+///
+/// A `-> Result<T>` target is flattened so its own error passes through.
+/// An infallible target can list `where buffer: (width, height, channels)`
+/// to have each buffer's length checked before the call.
+/// Every argument is asserted unwind-safe: a panic discards the result, and
+/// `&mut` buffers are documented as possibly half-written.
+macro_rules! safe_fns {
+    () => {};
+    (
+        $name:ident = $module:ident::$func:ident($($arg:ident: $ty:ty),* $(,)?) -> Result<$ret:ty>;
+        $($rest:tt)*
+    ) => {
+        #[doc = concat!(
+            "[`", stringify!($module), "::", stringify!($func), "`] returning an error instead of panicking"
+        )]
+        #[wasm_bindgen]
+        #[allow(clippy::too_many_arguments)]
+        pub fn $name($($arg: $ty),*) -> Result<$ret, Error> {
+            catch_panic(AssertUnwindSafe(|| $module::$func($($arg),*))).and_then(|result| result)
+        }
+
+        safe_fns!($($rest)*);
+    };
+    (
+        $name:ident = $module:ident::$func:ident($($arg:ident: $ty:ty),* $(,)?) -> $ret:ty
+            $(where $($buf:ident: ($w:expr, $h:expr, $c:expr)),+ $(,)?)?;
+        $($rest:tt)*
+    ) => {
+        #[doc = concat!(
+            "[`", stringify!($module), "::", stringify!($func), "`] returning an error instead of panicking"
+        )]
+        #[wasm_bindgen]
+        #[allow(clippy::too_many_arguments)]
+        pub fn $name($($arg: $ty),*) -> Result<$ret, Error> {
+            $($(check_len(stringify!($buf), $buf.len(), $w as usize * $h as usize * $c)?;)+)?
+            catch_panic(AssertUnwindSafe(|| $module::$func($($arg),*)))
+        }
+
+        safe_fns!($($rest)*);
+    };
+    (
+        $name:ident = $module:ident::$func:ident($($arg:ident: $ty:ty),* $(,)?);
+        $($rest:tt)*
+    ) => {
+        #[doc = concat!(
+            "[`", stringify!($module), "::", stringify!($func), "`] returning an error instead of panicking"
+        )]
+        #[wasm_bindgen]
+        #[allow(clippy::too_many_arguments)]
+        pub fn $name($($arg: $ty),*) -> Result<(), Error> {
+            catch_panic(AssertUnwindSafe(|| $module::$func($($arg),*)))
+        }
+
+        safe_fns!($($rest)*);
+    };
+}
+
+safe_fns! {
+    generate_sdf_safe = sdf::generate_sdf(
+        alpha_data: &[u8],
+        width: u32,
+        height: u32,
+        max_distance: f32,
+    ) -> Vec<u8>
+        where alpha_data: (width, height, 1);
+    generate_sdf_iso_safe = sdf::generate_sdf_iso(
+        alpha_data: &[u8],
+        width: u32,
+        height: u32,
+        max_distance: f32,
+        iso: f32,
+    ) -> Vec<u8>
+        where alpha_data: (width, height, 1);
+    generate_sdf_metric_safe = sdf::generate_sdf_metric(
+        alpha_data: &[u8],
+        width: u32,
+        height: u32,
+        max_distance: f32,
+        metric: DistanceMetric,
+    ) -> Vec<u8>
+        where alpha_data: (width, height, 1);
+    generate_sdf_opts_safe = sdf::generate_sdf_opts(
+        alpha_data: &[u8],
+        width: u32,
+        height: u32,
+        options: &SdfOptions,
+    ) -> Vec<u8>
+        where alpha_data: (width, height, 1);
+    generate_sdf_schedule_safe = sdf::generate_sdf_schedule(
+        alpha_data: &[u8],
+        width: u32,
+        height: u32,
+        max_distance: f32,
+        steps: &[u32],
+    ) -> Vec<u8>
+        where alpha_data: (width, height, 1);
+    generate_sdf_f16_safe = sdf::generate_sdf_f16(
+        alpha_data: &[u8],
+        width: u32,
+        height: u32,
+        max_distance: f32,
+    ) -> Vec<u16>
+        where alpha_data: (width, height, 1);
+    generate_sdf_pyramid_safe = sdf::generate_sdf_pyramid(
+        alpha_data: &[u8],
+        width: u32,
+        height: u32,
+        max_distance: f32,
+        levels: u32,
+    ) -> Vec<MipmapLevel>
+        where alpha_data: (width, height, 1);
+    generate_sdf_into_safe = sdf::generate_sdf_into(
+        alpha_data: &[u8],
+        width: u32,
+        height: u32,
+        max_distance: f32,
+        seeds: &mut [i32],
+        distances: &mut [f32],
+        out: &mut [u8],
+    ) -> Result<()>;
+    generate_sdf_split_safe = sdf::generate_sdf_split(
+        alpha_data: &[u8],
+        width: u32,
+        height: u32,
+        max_distance: f32,
+    ) -> SplitSdf
+        where alpha_data: (width, height, 1);
+    crop_sdf_safe = sdf::crop_sdf(
+        sdf: &[u8],
+        width: u32,
+        height: u32,
+        rx: u32,
+        ry: u32,
+        rw: u32,
+        rh: u32,
+    ) -> Result<Vec<u8>>;
+    blend_sdf_safe = sdf::blend_sdf(
+        prev: &[u8],
+        curr: &[u8],
+        alpha: f32,
+    ) -> Result<Vec<u8>>;
+    combine_sdf_safe = sdf::combine_sdf(
+        a: &[u8],
+        b: &[u8],
+        op: BoolOp,
+        max_distance: f32,
+    ) -> Result<Vec<u8>>;
+    count_edge_seeds_safe = sdf::count_edge_seeds(
+        alpha_data: &[u8],
+        width: u32,
+        height: u32,
+        threshold: u8,
+    ) -> u32
+        where alpha_data: (width, height, 1);
+}
+
+safe_fns! {
+    max_mipmap_levels_safe = mipmap::max_mipmap_levels(
+        width: u32,
+        height: u32,
+    ) -> u32;
+    generate_mipmaps_safe = mipmap::generate_mipmaps(
+        image_data: &[u8],
+        width: u32,
+        height: u32,
+        levels: u32,
+    ) -> Vec<MipmapLevel>
+        where image_data: (width, height, 4);
+    generate_mipmaps_full_safe = mipmap::generate_mipmaps_full(
+        image_data: &[u8],
+        width: u32,
+        height: u32,
+    ) -> Vec<MipmapLevel>
+        where image_data: (width, height, 4);
+    generate_mipmaps_opts_safe = mipmap::generate_mipmaps_opts(
+        image_data: &[u8],
+        width: u32,
+        height: u32,
+        levels: u32,
+        options: &MipmapOptions,
+    ) -> Vec<MipmapLevel>
+        where image_data: (width, height, 4);
+    generate_mipmaps_region_safe = mipmap::generate_mipmaps_region(
+        image_data: &[u8],
+        width: u32,
+        height: u32,
+        rx: u32,
+        ry: u32,
+        rw: u32,
+        rh: u32,
+        levels: u32,
+    ) -> Result<Vec<MipmapLevel>>;
+    generate_mipmaps_custom_safe = mipmap::generate_mipmaps_custom(
+        image_data: &[u8],
+        width: u32,
+        height: u32,
+        levels: u32,
+        kernel: &[f32],
+        kernel_size: u32,
+    ) -> Result<Vec<MipmapLevel>>;
+    generate_mipmaps_u16_safe = mipmap::generate_mipmaps_u16(
+        image_data: &[u16],
+        width: u32,
+        height: u32,
+        levels: u32,
+    ) -> Result<Vec<MipmapLevel16>>;
+    generate_mipmaps_stepped_safe = mipmap::generate_mipmaps_stepped(
+        image_data: &[u8],
+        width: u32,
+        height: u32,
+        levels: u32,
+        options: &MipmapOptions,
+    ) -> MipmapStepper
+        where image_data: (width, height, 4);
+    downsample_half_safe = mipmap::downsample_half(
+        image_data: &[u8],
+        width: u32,
+        height: u32,
+    ) -> MipmapLevel
+        where image_data: (width, height, 4);
+    select_mipmap_level_safe = mipmap::select_mipmap_level(
+        output_size: u32,
+        source_size: u32,
+        mipmap_count: u32,
+    ) -> u32;
+}
+
+safe_fns! {
+    calculate_centroid_safe = normalize::calculate_centroid(
+        alpha_data: &[u8],
+        width: u32,
+        height: u32,
+        threshold: u8,
+    ) -> Centroid
+        where alpha_data: (width, height, 1);
+    principal_axis_safe = normalize::principal_axis(
+        alpha_data: &[u8],
+        width: u32,
+        height: u32,
+        threshold: u8,
+    ) -> f32
+        where alpha_data: (width, height, 1);
+    quantize_centroid_safe = normalize::quantize_centroid(
+        centroid: &Centroid,
+        grid: f32,
+    ) -> Centroid;
+    calculate_centroid_banded_safe = normalize::calculate_centroid_banded(
+        alpha_data: &[u8],
+        width: u32,
+        height: u32,
+        min_threshold: u8,
+        max_threshold: u8,
+    ) -> Centroid
+        where alpha_data: (width, height, 1);
+    calculate_centroid_exact_safe = normalize::calculate_centroid_exact(
+        alpha_data: &[u8],
+        width: u32,
+        height: u32,
+        threshold: u8,
+    ) -> Centroid
+        where alpha_data: (width, height, 1);
+    calculate_centroid_approx_safe = normalize::calculate_centroid_approx(
+        alpha_data: &[u8],
+        width: u32,
+        height: u32,
+        threshold: u8,
+        stride: u32,
+    ) -> Centroid
+        where alpha_data: (width, height, 1);
+    suggest_crop_safe = normalize::suggest_crop(
+        alpha_data: &[u8],
+        width: u32,
+        height: u32,
+        threshold: u8,
+        margin_ratio: f32,
+    ) -> Bounds
+        where alpha_data: (width, height, 1);
+    crop_and_resize_safe = normalize::crop_and_resize(
+        image_data: &[u8],
+        width: u32,
+        height: u32,
+        threshold: u8,
+        out_width: u32,
+        out_height: u32,
+        margin: u32,
+    ) -> Vec<u8>
+        where image_data: (width, height, 4);
+    coverage_area_safe = normalize::coverage_area(alpha_data: &[u8]) -> f32;
+    label_components_safe = normalize::label_components(
+        alpha_data: &[u8],
+        width: u32,
+        height: u32,
+        threshold: u8,
+    ) -> Vec<u32>
+        where alpha_data: (width, height, 1);
+    calculate_centroids_multi_safe = normalize::calculate_centroids_multi(
+        alpha_data: &[u8],
+        width: u32,
+        height: u32,
+        threshold: u8,
+        min_area: u32,
+    ) -> Vec<Centroid>
+        where alpha_data: (width, height, 1);
+    convex_hull_safe = normalize::convex_hull(
+        alpha_data: &[u8],
+        width: u32,
+        height: u32,
+        threshold: u8,
+    ) -> Vec<f32>
+        where alpha_data: (width, height, 1);
+    normalize_matte_safe = normalize::normalize_matte(
+        image_data: &mut [u8],
+        width: u32,
+        height: u32,
+        edge_softness: f32,
+    ) -> Centroid
+        where image_data: (width, height, 4);
+    normalize_matte_with_threshold_safe = normalize::normalize_matte_with_threshold(
+        image_data: &mut [u8],
+        width: u32,
+        height: u32,
+        edge_softness: f32,
+        threshold: u8,
+    ) -> Centroid
+        where image_data: (width, height, 4);
+    normalize_matte_straight_safe = normalize::normalize_matte_straight(
+        image_data: &mut [u8],
+        width: u32,
+        height: u32,
+        edge_softness: f32,
+    ) -> Centroid
+        where image_data: (width, height, 4);
+    normalize_matte_into_safe = normalize::normalize_matte_into(
+        src: &[u8],
+        dst: &mut [u8],
+        width: u32,
+        height: u32,
+        edge_softness: f32,
+    ) -> Result<Centroid>;
+    smooth_alpha_radius_safe = normalize::smooth_alpha_radius(
+        edge_softness: f32,
+    ) -> u32;
+    dilate_alpha_safe = normalize::dilate_alpha(
+        alpha_data: &[u8],
+        width: u32,
+        height: u32,
+        radius: u32,
+    ) -> Vec<u8>
+        where alpha_data: (width, height, 1);
+    erode_alpha_safe = normalize::erode_alpha(
+        alpha_data: &[u8],
+        width: u32,
+        height: u32,
+        radius: u32,
+    ) -> Vec<u8>
+        where alpha_data: (width, height, 1);
+    dilate_alpha_xy_safe = normalize::dilate_alpha_xy(
+        alpha_data: &[u8],
+        width: u32,
+        height: u32,
+        radius_x: u32,
+        radius_y: u32,
+    ) -> Vec<u8>
+        where alpha_data: (width, height, 1);
+    erode_alpha_xy_safe = normalize::erode_alpha_xy(
+        alpha_data: &[u8],
+        width: u32,
+        height: u32,
+        radius_x: u32,
+        radius_y: u32,
+    ) -> Vec<u8>
+        where alpha_data: (width, height, 1);
+    morphological_gradient_safe = normalize::morphological_gradient(
+        alpha_data: &[u8],
+        width: u32,
+        height: u32,
+        radius: u32,
+    ) -> Vec<u8>
+        where alpha_data: (width, height, 1);
+    dilate_alpha_f_safe = normalize::dilate_alpha_f(
+        alpha_data: &[u8],
+        width: u32,
+        height: u32,
+        radius: f32,
+    ) -> Vec<u8>
+        where alpha_data: (width, height, 1);
+    despeckle_alpha_safe = normalize::despeckle_alpha(
+        alpha_data: &[u8],
+        width: u32,
+        height: u32,
+    ) -> Vec<u8>
+        where alpha_data: (width, height, 1);
+    extend_edges_safe = normalize::extend_edges(
+        image_data: &[u8],
+        width: u32,
+        height: u32,
+        iterations: u32,
+    ) -> Vec<u8>
+        where image_data: (width, height, 4);
+    alpha_levels_safe = normalize::alpha_levels(
+        alpha_data: &[u8],
+        in_low: u8,
+        in_high: u8,
+        out_low: u8,
+        out_high: u8,
+    ) -> Vec<u8>;
+    equalize_alpha_safe = normalize::equalize_alpha(
+        alpha_data: &[u8],
+    ) -> Vec<u8>;
+    matte_from_luminance_safe = normalize::matte_from_luminance(
+        image_data: &[u8],
+        width: u32,
+        height: u32,
+        low: u8,
+        high: u8,
+    ) -> Vec<u8>
+        where image_data: (width, height, 4);
+    despill_safe = normalize::despill(
+        image_data: &[u8],
+        width: u32,
+        height: u32,
+        spill_color: u32,
+        strength: f32,
+    ) -> Vec<u8>
+        where image_data: (width, height, 4);
+    guided_matte_refine_safe = normalize::guided_matte_refine(
+        image_data: &[u8],
+        alpha_data: &[u8],
+        width: u32,
+        height: u32,
+        radius: u32,
+        epsilon: f32,
+    ) -> Vec<u8>
+        where image_data: (width, height, 4), alpha_data: (width, height, 1);
+    antialias_binary_mask_safe = normalize::antialias_binary_mask(
+        alpha_data: &[u8],
+        width: u32,
+        height: u32,
+    ) -> Vec<u8>
+        where alpha_data: (width, height, 1);
+    round_corners_safe = normalize::round_corners(
+        alpha_data: &[u8],
+        width: u32,
+        height: u32,
+        radius: f32,
+    ) -> Vec<u8>
+        where alpha_data: (width, height, 1);
+    feather_outer_safe = normalize::feather_outer(
+        alpha_data: &[u8],
+        width: u32,
+        height: u32,
+        feather_px: f32,
+    ) -> Vec<u8>
+        where alpha_data: (width, height, 1);
+    generate_trimap_safe = normalize::generate_trimap(
+        alpha_data: &[u8],
+        width: u32,
+        height: u32,
+        erode_radius: u32,
+        dilate_radius: u32,
+    ) -> Vec<u8>
+        where alpha_data: (width, height, 1);
+    conservative_mask_safe = normalize::conservative_mask(
+        alpha_data: &[u8],
+        width: u32,
+        height: u32,
+    ) -> Vec<u8>
+        where alpha_data: (width, height, 1);
+}
+
+safe_fns! {
+    gaussian_blur_rgba_safe = filter::gaussian_blur_rgba(
+        image_data: &[u8],
+        width: u32,
+        height: u32,
+        sigma: f32,
+    ) -> Vec<u8>
+        where image_data: (width, height, 4);
+    unsharp_mask_safe = filter::unsharp_mask(
+        image_data: &[u8],
+        width: u32,
+        height: u32,
+        amount: f32,
+        radius: f32,
+    ) -> Vec<u8>
+        where image_data: (width, height, 4);
+    bloom_mask_safe = filter::bloom_mask(
+        image_data: &[u8],
+        width: u32,
+        height: u32,
+        luminance_threshold: f32,
+        blur_radius: u32,
+    ) -> Vec<u8>
+        where image_data: (width, height, 4);
+}
+
+safe_fns! {
+    sdf_stroke_safe = effects::sdf_stroke(
+        sdf: &[u8],
+        width: u32,
+        height: u32,
+        stroke_width: f32,
+        max_distance: f32,
+    ) -> Vec<u8>
+        where sdf: (width, height, 1);
+    sdf_isolines_safe = effects::sdf_isolines(
+        sdf: &[u8],
+        width: u32,
+        height: u32,
+        spacing: f32,
+        max_distance: f32,
+    ) -> Vec<u8>
+        where sdf: (width, height, 1);
+    dashed_outline_safe = effects::dashed_outline(
+        sdf: &[u8],
+        width: u32,
+        height: u32,
+        dash_length: f32,
+        phase: f32,
+        stroke_width: f32,
+        max_distance: f32,
+    ) -> Vec<u8>
+        where sdf: (width, height, 1);
+    sdf_stroke_adaptive_safe = effects::sdf_stroke_adaptive(
+        sdf: &[u8],
+        width: u32,
+        height: u32,
+        stroke_width: f32,
+        max_distance: f32,
+        corner_boost: f32,
+    ) -> Vec<u8>
+        where sdf: (width, height, 1);
+    inset_outline_safe = effects::inset_outline(
+        alpha_data: &[u8],
+        width: u32,
+        height: u32,
+        inset_px: f32,
+    ) -> Vec<u8>
+        where alpha_data: (width, height, 1);
+    sdf_ao_safe = effects::sdf_ao(
+        sdf: &[u8],
+        width: u32,
+        height: u32,
+        radius: f32,
+        max_distance: f32,
+    ) -> Vec<u8>
+        where sdf: (width, height, 1);
+    sdf_thickness_safe = effects::sdf_thickness(
+        sdf: &[u8],
+        width: u32,
+        height: u32,
+        max_distance: f32,
+        curve: f32,
+    ) -> Vec<u8>
+        where sdf: (width, height, 1);
+    sdf_to_depth_safe = effects::sdf_to_depth(
+        sdf: &[u8],
+        width: u32,
+        height: u32,
+        max_distance: f32,
+        near: f32,
+        far: f32,
+    ) -> Vec<u8>
+        where sdf: (width, height, 1);
+}
+
+safe_fns! {
+    srgb_to_linear_slice_safe = color::srgb_to_linear_slice(
+        data: &[u8],
+    ) -> Vec<f32>;
+    linear_to_srgb_slice_safe = color::linear_to_srgb_slice(
+        data: &[f32],
+    ) -> Vec<u8>;
+    to_grayscale_safe = color::to_grayscale(
+        image_data: &[u8],
+        width: u32,
+        height: u32,
+        gamma_correct: bool,
+    ) -> Result<Vec<u8>>;
+    region_average_colors_safe = color::region_average_colors(
+        image_data: &[u8],
+        width: u32,
+        height: u32,
+        regions: u32,
+    ) -> Result<Vec<u32>>;
+}
+
+safe_fns! {
+    rle_encode_safe = mask::rle_encode(mask: &[u8]) -> Vec<u8>;
+    rle_decode_safe = mask::rle_decode(encoded: &[u8]) -> Result<Vec<u8>>;
+    pack_sdf_4bit_safe = mask::pack_sdf_4bit(sdf: &[u8]) -> Vec<u8>;
+    unpack_sdf_4bit_safe = mask::unpack_sdf_4bit(
+        packed: &[u8],
+        pixel_count: usize,
+    ) -> Result<Vec<u8>>;
+    diff_mask_safe = mask::diff_mask(
+        a: &[u8],
+        b: &[u8],
+        width: u32,
+        height: u32,
+        threshold: u8,
+    ) -> Result<Bounds>;
+    ssim_safe = mask::ssim(
+        a: &[u8],
+        b: &[u8],
+        width: u32,
+        height: u32,
+    ) -> Result<f32>;
+}
+
+safe_fns! {
+    hash_buffer_safe = hash::hash_buffer(data: &[u8]) -> u64;
+}
+
+safe_fns! {
+    sample_bilinear_rgba_safe = sample::sample_bilinear_rgba(
+        data: &[u8],
+        width: u32,
+        height: u32,
+        u: f32,
+        v: f32,
+        wrap: bool,
+        premultiplied_output: bool,
+    ) -> Result<Vec<u8>>;
+}
+
+safe_fns! {
+    generate_sdf_frame_safe = frame::generate_sdf_frame(
+        frame: &Frame,
+        max_distance: f32,
+    ) -> Vec<u8>;
+    generate_mipmaps_frame_safe = frame::generate_mipmaps_frame(
+        frame: &Frame,
+        levels: u32,
+    ) -> Vec<MipmapLevel>;
+    calculate_centroid_frame_safe = frame::calculate_centroid_frame(
+        frame: &Frame,
+        threshold: u8,
+    ) -> Centroid;
+    generate_sdf_channel_safe = frame::generate_sdf_channel(
+        image_data: &[u8],
+        width: u32,
+        height: u32,
+        channel: u8,
+        max_distance: f32,
+    ) -> Result<Vec<u8>>;
+    calculate_centroid_channel_safe = frame::calculate_centroid_channel(
+        image_data: &[u8],
+        width: u32,
+        height: u32,
+        channel: u8,
+        threshold: u8,
+    ) -> Result<Centroid>;
+    fill_rgba_safe = frame::fill_rgba(
+        width: u32,
+        height: u32,
+        color: u32,
+    ) -> Vec<u8>;
+    clear_rgba_safe = frame::clear_rgba(data: &mut [u8]);
+    slice_grid_safe = frame::slice_grid(
+        image_data: &[u8],
+        width: u32,
+        height: u32,
+        cols: u32,
+        rows: u32,
+    ) -> Result<Vec<Frame>>;
+}
+
+safe_fns! {
+    poisson_blend_safe = composite::poisson_blend(
+        target: &mut [u8],
+        source: &[u8],
+        mask: &[u8],
+        width: u32,
+        height: u32,
+        offset_x: i32,
+        offset_y: i32,
+    ) -> Result<()>;
+    crossfade_safe = composite::crossfade(
+        a: &[u8],
+        b: &[u8],
+        t: f32,
+        premultiplied: bool,
+    ) -> Result<Vec<u8>>;
+}
+
+safe_fns! {
+    estimate_cost_safe = crate::estimate_cost(
+        width: u32,
+        height: u32,
+        levels: u32,
+        include_sdf: bool,
+    ) -> f32;
+}
+
+#[wasm_bindgen]
+impl SdfGenerator {
+    /// [`SdfGenerator::generate`] returning an error instead of panicking
+    pub fn generate_safe(
+        &mut self,
+        alpha_data: &[u8],
+        max_distance: f32,
+    ) -> Result<Vec<u8>, Error> {
+        check_len("alpha_data", alpha_data.len(), self.width() as usize * self.height() as usize)?;
+        catch_panic(AssertUnwindSafe(|| self.generate(alpha_data, max_distance)))
+    }
+
+    /// [`SdfGenerator::generate_warmstart`] returning an error instead of panicking
+    pub fn generate_warmstart_safe(
+        &mut self,
+        alpha_data: &[u8],
+        prev_seeds: &[i32],
+        max_distance: f32,
+    ) -> Result<Vec<u8>, Error> {
+        catch_panic(AssertUnwindSafe(|| {
+            self.generate_warmstart(alpha_data, prev_seeds, max_distance)
+        }))
+        .and_then(|result| result)
+    }
+}
+
+#[wasm_bindgen]
+impl BatchJob {
+    /// [`BatchJob::step`] returning an error instead of panicking
+    pub fn step_safe(&mut self) -> Result<bool, Error> {
+        catch_panic(AssertUnwindSafe(|| self.step()))
+    }
+}
+
+#[wasm_bindgen]
+impl MipmapStepper {
+    /// [`MipmapStepper::next_level`] returning an error instead of panicking
+    pub fn next_level_safe(&mut self) -> Result<Option<MipmapLevel>, Error> {
+        catch_panic(AssertUnwindSafe(|| self.next_level()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_panic_is_error() {
+        let result = catch_panic(|| -> u8 { panic!("index out of bounds") });
+        assert!(
+            matches!(&result, Err(Error::Panic { message }) if message == "index out of bounds"),
+            "{:?}",
+            result
+        );
+
+        // Well-formed input passes straight through
+        let alpha = vec![255u8; 16];
+        let expected = sdf::generate_sdf(&alpha, 4, 4, 8.0);
+        assert_eq!(generate_sdf_safe(&alpha, 4, 4, 8.0).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_short_buffers_checked_up_front() {
+        // Uniform and non-uniform alike, so no fast path can skip the check
+        for short in [[255u8; 10], [0, 255, 0, 255, 0, 255, 0, 255, 0, 255]] {
+            let result = generate_sdf_opts_safe(&short, 64, 64, &SdfOptions::default());
+            assert!(matches!(result, Err(Error::LengthMismatch { .. })), "{:?}", result);
+            assert!(matches!(
+                SdfGenerator::new(64, 64).generate_safe(&short, 8.0),
+                Err(Error::LengthMismatch { .. })
+            ));
+        }
+
+        let mut image = vec![128u8; 16];
+        let result = extend_edges_safe(&image, 8, 8, 2);
+        assert!(matches!(result, Err(Error::LengthMismatch { .. })));
+        assert!(matches!(
+            normalize_matte_safe(&mut image, 8, 8, 0.5),
+            Err(Error::LengthMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_wraps_other_modules() {
+        let short = [255u8; 10];
+        assert!(matches!(
+            gaussian_blur_rgba_safe(&short, 32, 32, 2.0),
+            Err(Error::LengthMismatch { .. })
+        ));
+        assert!(matches!(
+            sdf_stroke_safe(&short, 32, 32, 2.0, 8.0),
+            Err(Error::LengthMismatch { .. })
+        ));
+
+        // A target's own error passes through rather than becoming a panic
+        let result = crossfade_safe(&[0; 4], &[0; 8], 0.5, false);
+        assert!(matches!(result, Err(Error::LengthMismatch { .. })), "{:?}", result);
+
+        let mut generator = SdfGenerator::new(16, 16);
+        assert!(generator.generate_safe(&[0, 255, 0, 255], 8.0).is_err());
+    }
+}
@@ -0,0 +1,158 @@
+//! Texture Sampling
+//!
+//! CPU-side lookups into generated SDFs and color atlases at arbitrary UV
+//! coordinates, matching what a GPU sampler would return.
+
+use wasm_bindgen::prelude::*;
+
+use crate::color::to_u8;
+use crate::error::{check_len, Error};
+
+/// Bilinearly sample an RGBA image at a normalized coordinate
+///
+/// Texel `i` is centered at `(i + 0.5) / width`, as on the GPU. The four
+/// nearest texels are blended in premultiplied space, so a transparent
+/// neighbor fades coverage without darkening the color.
+///
+/// # Arguments
+///
+/// * `data` - RGBA pixel data (straight alpha)
+/// * `width` - Image width
+/// * `height` - Image height
+/// * `u` - Horizontal coordinate, 0.0-1.0 across the image
+/// * `v` - Vertical coordinate, 0.0-1.0 down the image
+/// * `wrap` - Repeat the image outside 0-1 instead of clamping to the edge
+/// * `premultiplied_output` - Return premultiplied rather than straight RGB
+///
+/// # Returns
+///
+/// One RGBA pixel as 4 bytes; transparent black for an empty image
+///
+/// # Errors
+///
+/// Returns [`Error::LengthMismatch`] if `data` doesn't match the
+/// dimensions.
+#[wasm_bindgen]
+pub fn sample_bilinear_rgba(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    u: f32,
+    v: f32,
+    wrap: bool,
+    premultiplied_output: bool,
+) -> Result<Vec<u8>, Error> {
+    let w = width as usize;
+    let h = height as usize;
+    check_len("data", data.len(), w * h * 4)?;
+    if w == 0 || h == 0 {
+        return Ok(vec![0; 4]);
+    }
+
+    let x = u * w as f32 - 0.5;
+    let y = v * h as f32 - 0.5;
+    Ok(bilinear_texel(data, w, h, x, y, wrap, premultiplied_output).to_vec())
+}
+
+/// Bilinear sample at a texel-space position (texel `i` centered at `i`)
+///
+/// The allocation-free core of [`sample_bilinear_rgba`] for callers
+/// sampling many points. `w` and `h` must be nonzero.
+pub(crate) fn bilinear_texel(
+    data: &[u8],
+    w: usize,
+    h: usize,
+    x: f32,
+    y: f32,
+    wrap: bool,
+    premultiplied_output: bool,
+) -> [u8; 4] {
+    let (x0, x1, fx) = texel_pair(x, w, wrap);
+    let (y0, y1, fy) = texel_pair(y, h, wrap);
+
+    let taps = [
+        (x0, y0, (1.0 - fx) * (1.0 - fy)),
+        (x1, y0, fx * (1.0 - fy)),
+        (x0, y1, (1.0 - fx) * fy),
+        (x1, y1, fx * fy),
+    ];
+
+    let mut sum = [0.0f32; 4];
+    for (x, y, weight) in taps {
+        let idx = (y * w + x) * 4;
+        let a = data[idx + 3] as f32;
+        for c in 0..3 {
+            sum[c] += data[idx + c] as f32 * a / 255.0 * weight;
+        }
+        sum[3] += a * weight;
+    }
+
+    let a = sum[3];
+    let scale = if premultiplied_output || a <= 0.0 {
+        1.0
+    } else {
+        255.0 / a
+    };
+
+    let mut result = [0u8; 4];
+    for c in 0..3 {
+        result[c] = to_u8(sum[c] * scale);
+    }
+    result[3] = to_u8(a);
+    result
+}
+
+/// Neighboring texel indices and blend factor for a texel-space coordinate
+fn texel_pair(coord: f32, size: usize, wrap: bool) -> (usize, usize, f32) {
+    // Reduce a wrapped coordinate first, so huge ones don't saturate the cast
+    let coord = if wrap { coord.rem_euclid(size as f32) } else { coord };
+    let base = coord.floor();
+    let frac = coord - base;
+    let i0 = base as i64;
+    let i1 = i0.saturating_add(1);
+
+    if wrap {
+        let n = size as i64;
+        (i0.rem_euclid(n) as usize, i1.rem_euclid(n) as usize, frac)
+    } else {
+        let last = size as i64 - 1;
+        (i0.clamp(0, last) as usize, i1.clamp(0, last) as usize, frac)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bilinear_centers_and_midpoint() {
+        // Opaque red beside transparent black
+        let data = [255, 0, 0, 255, 0, 0, 0, 0];
+        let sample =
+            |u, wrap, premul| sample_bilinear_rgba(&data, 2, 1, u, 0.5, wrap, premul).unwrap();
+
+        // Texel centers return the texels themselves
+        assert_eq!(sample(0.25, false, false), [255, 0, 0, 255]);
+        assert_eq!(sample(0.75, false, false), [0, 0, 0, 0]);
+
+        // Halfway: coverage halves, but straight color stays pure red
+        assert_eq!(sample(0.5, false, false), [255, 0, 0, 128]);
+        assert_eq!(sample(0.5, false, true), [128, 0, 0, 128]);
+
+        // Past the right edge: clamping holds the last texel, wrapping
+        // blends back toward the first
+        assert_eq!(sample(1.0, false, true), [0, 0, 0, 0]);
+        assert_eq!(sample(1.0, true, true), [128, 0, 0, 128]);
+
+        // Far-off coordinates wrap or clamp instead of overflowing
+        for u in [1e30, -1e30, f32::MAX] {
+            assert_eq!(sample(u, true, false).len(), 4);
+            assert_eq!(sample(u, false, false).len(), 4);
+        }
+        assert_eq!(sample(1e30, false, false), [0, 0, 0, 0]);
+        assert_eq!(sample(-1e30, false, false), [255, 0, 0, 255]);
+
+        let result = sample_bilinear_rgba(&data[..4], 2, 1, 0.5, 0.5, false, false);
+        assert!(matches!(result, Err(Error::LengthMismatch { .. })));
+    }
+}
@@ -16,6 +16,173 @@
 
 use wasm_bindgen::prelude::*;
 
+use crate::color::to_u8;
+use crate::error::{check_len, check_region, Error};
+use crate::mipmap::{extract_region, max_mipmap_levels, MipmapLevel};
+use crate::normalize::calculate_centroid;
+use crate::OPAQUE_THRESHOLD;
+
+/// Distance metric used to measure pixel-to-seed distance
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DistanceMetric {
+    /// Straight-line distance; round isolines
+    Euclidean,
+    /// Sum of axis offsets; diamond-shaped isolines
+    Manhattan,
+    /// Largest axis offset; square isolines
+    Chebyshev,
+}
+
+impl DistanceMetric {
+    /// Distance between two positions (in pixels) under this metric
+    fn distance(self, x1: f32, y1: f32, x2: f32, y2: f32) -> f32 {
+        let dx = (x1 - x2).abs();
+        let dy = (y1 - y2).abs();
+        match self {
+            DistanceMetric::Euclidean => (dx * dx + dy * dy).sqrt(),
+            DistanceMetric::Manhattan => dx + dy,
+            DistanceMetric::Chebyshev => dx.max(dy),
+        }
+    }
+}
+
+/// Which side of the silhouette an SDF is computed for
+///
+/// Effects that only sample one side (an outer glow, an inner bevel) can
+/// skip normalizing the other. Skipped pixels receive a sentinel: 0 for
+/// skipped inside pixels (a value [`generate_sdf`] never produces, since
+/// its deepest inside value is 1) and 255 for skipped outside pixels.
+/// Seeding and the jump flood still cover both sides, because pixels on
+/// the unused side relay seeds to the used side during propagation.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SdfRegion {
+    /// Inside and outside distances
+    Both,
+    /// Inside distances only; outside pixels are 255
+    InsideOnly,
+    /// Outside distances only; inside pixels are 0
+    OutsideOnly,
+}
+
+impl SdfRegion {
+    /// Sentinel for a pixel on the given side, or `None` if it is computed
+    fn sentinel(self, inside: bool) -> Option<u8> {
+        match (self, inside) {
+            (SdfRegion::InsideOnly, false) => Some(255),
+            (SdfRegion::OutsideOnly, true) => Some(0),
+            _ => None,
+        }
+    }
+}
+
+/// Denominator of the final distance-to-byte mapping
+///
+/// Every encoding stores a signed distance `d` (already clamped to
+/// `±max_distance`) as `128 + d / scale * 127`, clamped to 0-255. The
+/// variants choose `scale`.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SdfScale {
+    /// `scale = max_distance`: the full byte range spans `±max_distance`.
+    /// The default, and the encoding SDF effects expect.
+    MaxDistance,
+    /// `scale = 127`: one byte step per pixel, so `byte - 128` is the
+    /// distance in pixels. Saturates beyond 127px.
+    Pixels,
+    /// `scale = max(width, height)`: distance as a fraction of the image's
+    /// larger dimension, so one shader constant works across sprite sizes.
+    /// Set `max_distance` to at least that dimension to avoid clamping.
+    MaxDimension,
+}
+
+/// Options for SDF generation
+///
+/// Grouped so the entry-point signature stays small as options grow. Use
+/// [`SdfOptions::new`] (or `Default`) and override individual fields.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug)]
+pub struct SdfOptions {
+    /// Maximum distance to compute (affects precision)
+    pub max_distance: f32,
+    /// Alpha (0-255) at or above which a pixel counts as inside
+    /// (default [`OPAQUE_THRESHOLD`])
+    pub threshold: u8,
+    /// Metric for pixel-to-seed distance
+    pub metric: DistanceMetric,
+    /// Side(s) of the silhouette to compute
+    pub region: SdfRegion,
+    /// Run the JFA only over the sprite's bounding box plus a
+    /// `max_distance` margin, filling the rest with the far-field value.
+    /// Much faster for small sprites on large canvases. Results match the
+    /// unwindowed field up to the JFA's own approximation error, which
+    /// depends on grid size.
+    pub windowed: bool,
+    /// Place seeds at the sub-pixel position implied by partial coverage
+    /// instead of at edge pixel centers. A pixel with coverage `c` puts the
+    /// edge `c - 0.5` pixels from its center, away from the opaque side
+    /// along the alpha gradient, so the zero crossing lands where coverage
+    /// is 50%. Best for anti-aliased edges one or two pixels wide; every
+    /// partially transparent pixel becomes a seed.
+    pub coverage_seeding: bool,
+    /// Neighbors probed per pixel in each JFA pass: 8 (default) or 4.
+    /// Probing only the axis-aligned 4 roughly halves propagation cost, at
+    /// the price of occasional errors of a pixel or two where the nearest
+    /// seed lies diagonally. Any other value is treated as 8.
+    pub jfa_neighbors: u8,
+    /// Denominator of the distance-to-byte mapping
+    pub normalize_by: SdfScale,
+    /// Resolve equidistant seeds to the lower pixel index instead of
+    /// whichever neighbor the scan reaches first, and run one extra step-1
+    /// pass to clean up raster-order errors. Costs one pass; enough for
+    /// simple symmetric masks (a centered cross comes out mirror-symmetric),
+    /// but not a guarantee. See `symmetric` for one.
+    pub stable: bool,
+    /// Make the field exactly as symmetric as the mask: propagation runs in
+    /// all eight orientations of the grid (each combination of transpose
+    /// and horizontal/vertical flip) and every pixel keeps the nearest
+    /// result. Costs eight full propagations instead of one, plus a set of
+    /// oriented scratch buffers.
+    pub symmetric: bool,
+}
+
+impl Default for SdfOptions {
+    fn default() -> Self {
+        SdfOptions {
+            max_distance: 32.0,
+            threshold: OPAQUE_THRESHOLD,
+            metric: DistanceMetric::Euclidean,
+            region: SdfRegion::Both,
+            windowed: false,
+            coverage_seeding: false,
+            jfa_neighbors: 8,
+            normalize_by: SdfScale::MaxDistance,
+            stable: false,
+            symmetric: false,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl SdfOptions {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> SdfOptions {
+        SdfOptions::default()
+    }
+}
+
+impl SdfOptions {
+    /// The `normalize_by` denominator for a `w`×`h` image
+    fn encoding_scale(&self, w: usize, h: usize) -> f32 {
+        match self.normalize_by {
+            SdfScale::MaxDistance => self.max_distance,
+            SdfScale::Pixels => 127.0,
+            SdfScale::MaxDimension => w.max(h).max(1) as f32,
+        }
+    }
+}
+
 /// Generate a Signed Distance Field from an alpha mask
 ///
 /// # Arguments
@@ -31,108 +198,1264 @@ use wasm_bindgen::prelude::*;
 /// - 128 = on edge
 /// - < 128 = inside (higher = further from edge)
 /// - > 128 = outside (higher = further from edge)
+///
+/// A 1×1 mask has no edge, so its single value is fully inside or fully
+/// outside; an empty (0-sized) mask gives an empty field.
+#[wasm_bindgen]
+pub fn generate_sdf(
+    alpha_data: &[u8],
+    width: u32,
+    height: u32,
+    max_distance: f32,
+) -> Vec<u8> {
+    let options = SdfOptions {
+        max_distance,
+        ..SdfOptions::default()
+    };
+    generate_sdf_opts(alpha_data, width, height, &options)
+}
+
+/// Generate a Signed Distance Field with the edge at a chosen coverage
+///
+/// The zero crossing sits where alpha crosses `iso * 255` instead of at
+/// [`OPAQUE_THRESHOLD`]: pixels with `alpha / 255 >= iso` are inside.
+/// On a soft matte, a lower `iso` such as 0.25 takes in more of the
+/// falloff and gives a larger silhouette, e.g. for soft shadows. `iso =
+/// 0.5` is the same field as [`generate_sdf`]. This is
+/// [`SdfOptions::threshold`] expressed as coverage.
+///
+/// # Arguments
+///
+/// * `alpha_data` - Alpha channel as u8 array (0-255)
+/// * `width` - Image width in pixels
+/// * `height` - Image height in pixels
+/// * `max_distance` - Maximum distance to compute
+/// * `iso` - Coverage level of the edge, clamped to 0.0-1.0
+#[wasm_bindgen]
+pub fn generate_sdf_iso(
+    alpha_data: &[u8],
+    width: u32,
+    height: u32,
+    max_distance: f32,
+    iso: f32,
+) -> Vec<u8> {
+    let options = SdfOptions {
+        max_distance,
+        threshold: (iso.clamp(0.0, 1.0) * 255.0).ceil() as u8,
+        ..SdfOptions::default()
+    };
+    generate_sdf_opts(alpha_data, width, height, &options)
+}
+
+/// Generate a Signed Distance Field using a chosen distance metric
+///
+/// Same encoding as [`generate_sdf`]. Manhattan and Chebyshev produce the
+/// blocky, diamond- or square-shaped isolines used for pixel-art styling.
+#[wasm_bindgen]
+pub fn generate_sdf_metric(
+    alpha_data: &[u8],
+    width: u32,
+    height: u32,
+    max_distance: f32,
+    metric: DistanceMetric,
+) -> Vec<u8> {
+    let options = SdfOptions {
+        max_distance,
+        metric,
+        ..SdfOptions::default()
+    };
+    generate_sdf_opts(alpha_data, width, height, &options)
+}
+
+/// Generate a Signed Distance Field with explicit options
+///
+/// Same encoding as [`generate_sdf`], which is a thin wrapper over this
+/// with default options.
+///
+/// # Arguments
+///
+/// * `alpha_data` - Alpha channel as u8 array (0-255)
+/// * `width` - Image width in pixels
+/// * `height` - Image height in pixels
+/// * `options` - Generation options
+#[wasm_bindgen]
+pub fn generate_sdf_opts(
+    alpha_data: &[u8],
+    width: u32,
+    height: u32,
+    options: &SdfOptions,
+) -> Vec<u8> {
+    let w = width as usize;
+    let h = height as usize;
+    let scale = options.encoding_scale(w, h);
+
+    // Fast path: a uniformly inside or outside mask has no edges
+    if let Some(field) = uniform_field(alpha_data, w * h, options, scale) {
+        return field;
+    }
+
+    if options.windowed {
+        return windowed_sdf(alpha_data, w, h, options);
+    }
+
+    jump_flood_sdf(alpha_data, w, h, options, scale)
+}
+
+/// Generate a Signed Distance Field with a custom JFA step schedule
+///
+/// Runs one jump-flood pass per entry of `steps`, in order, instead of the
+/// built-in halving schedule (`max(w, h) / 2`, then halved down to 1).
+/// Intended for experimenting with variants such as JFA+1 (`[.., 2, 1, 1]`)
+/// or reversed schedules; zero steps are skipped. Same encoding as
+/// [`generate_sdf`].
+///
+/// # Arguments
+///
+/// * `alpha_data` - Alpha channel as u8 array (0-255)
+/// * `width` - Image width in pixels
+/// * `height` - Image height in pixels
+/// * `max_distance` - Maximum distance to compute (affects precision)
+/// * `steps` - Jump distance of each pass, in pixels
+#[wasm_bindgen]
+pub fn generate_sdf_schedule(
+    alpha_data: &[u8],
+    width: u32,
+    height: u32,
+    max_distance: f32,
+    steps: &[u32],
+) -> Vec<u8> {
+    let w = width as usize;
+    let h = height as usize;
+    let options = SdfOptions {
+        max_distance,
+        ..SdfOptions::default()
+    };
+
+    if let Some(field) = uniform_field(alpha_data, w * h, &options, options.max_distance) {
+        return field;
+    }
+
+    let mut seeds: Vec<i32> = vec![-1; w * h];
+    let mut distances: Vec<f32> = vec![f32::MAX; w * h];
+
+    seed_edges(alpha_data, w, h, options.threshold, &mut seeds, &mut distances);
+    for &step in steps.iter().filter(|&&step| step > 0) {
+        jump_pass(w, h, step as usize, &options, &[], &mut seeds, &mut distances);
+    }
+    drop(seeds);
+
+    let mut result = vec![0u8; w * h];
+    encode_field(alpha_data, &distances, &options, options.max_distance, &mut result);
+    result
+}
+
+/// Generate a Signed Distance Field as half-float pixel distances
+///
+/// For `r16float` textures: each entry is the IEEE 754 binary16 bit pattern
+/// of the signed distance in pixels (negative inside, positive outside),
+/// clamped to `±max_distance`. JS receives a `Uint16Array` to upload as is.
+/// Relative precision is 1/2048 (under 0.5px out to 1024px), far finer
+/// than the 8-bit field, and there is nothing to decode in the shader.
+///
+/// # Arguments
+///
+/// * `alpha_data` - Alpha channel as u8 array (0-255)
+/// * `width` - Image width in pixels
+/// * `height` - Image height in pixels
+/// * `max_distance` - Maximum distance to compute
+#[wasm_bindgen]
+pub fn generate_sdf_f16(
+    alpha_data: &[u8],
+    width: u32,
+    height: u32,
+    max_distance: f32,
+) -> Vec<u16> {
+    let options = SdfOptions {
+        max_distance,
+        ..SdfOptions::default()
+    };
+    let distances = seed_distances(alpha_data, width as usize, height as usize, &options);
+
+    distances
+        .iter()
+        .zip(alpha_data)
+        .map(|(&d, &a)| {
+            let dist = d.min(max_distance);
+            f32_to_f16(if a >= options.threshold { -dist } else { dist })
+        })
+        .collect()
+}
+
+/// Generate a pyramid of distance fields for level-of-detail lookups
+///
+/// The field is computed once at full resolution, then each coarser level
+/// averages 2×2 blocks of the level above and halves the result, since a
+/// distance measured in that level's pixels is half as many pixels. Levels
+/// are built from unquantized distances, so rounding doesn't accumulate
+/// down the pyramid. Every level uses [`generate_sdf`]'s encoding with the
+/// same `max_distance`, so level 0 is identical to it and level `n` reaches
+/// `max_distance / 2^n` of its own pixels before saturating.
+///
+/// # Arguments
+///
+/// * `alpha_data` - Alpha channel as u8 array (0-255)
+/// * `width` - Image width in pixels
+/// * `height` - Image height in pixels
+/// * `max_distance` - Maximum distance to compute at full resolution
+/// * `levels` - Number of levels, clamped to
+///   [`max_mipmap_levels`](crate::mipmap::max_mipmap_levels)
+///
+/// # Returns
+///
+/// Single-channel levels (`row_pitch == width`), finest first
+#[wasm_bindgen]
+pub fn generate_sdf_pyramid(
+    alpha_data: &[u8],
+    width: u32,
+    height: u32,
+    max_distance: f32,
+    levels: u32,
+) -> Vec<MipmapLevel> {
+    let levels = levels.clamp(1, max_mipmap_levels(width, height));
+    let options = SdfOptions {
+        max_distance,
+        ..SdfOptions::default()
+    };
+
+    let (mut w, mut h) = (width as usize, height as usize);
+    let mut signed: Vec<f32> = seed_distances(alpha_data, w, h, &options)
+        .iter()
+        .zip(alpha_data)
+        .map(|(&d, &a)| {
+            let dist = d.min(max_distance);
+            if a >= options.threshold { -dist } else { dist }
+        })
+        .collect();
+
+    let mut result = Vec::with_capacity(levels as usize);
+    for level in 0..levels {
+        if level > 0 {
+            let (nw, nh) = ((w / 2).max(1), (h / 2).max(1));
+            let at = |x: usize, y: usize| signed[y.min(h - 1) * w + x.min(w - 1)];
+            signed = (0..nw * nh)
+                .map(|i| {
+                    let (x, y) = (i % nw * 2, i / nw * 2);
+                    (at(x, y) + at(x + 1, y) + at(x, y + 1) + at(x + 1, y + 1)) / 8.0
+                })
+                .collect();
+            (w, h) = (nw, nh);
+        }
+
+        let data = signed.iter().map(|&d| encode_distance(d, max_distance)).collect();
+        result.push(MipmapLevel::from_channel(data, w as u32, h as u32));
+    }
+
+    result
+}
+
+/// Generate a Signed Distance Field into caller-provided buffers
+///
+/// Allocation-free variant of [`generate_sdf`] for per-frame use: the JFA
+/// scratch and the output all come from the caller (e.g. `Int32Array`,
+/// `Float32Array` and `Uint8Array` views kept alive in JS), so nothing is
+/// allocated or copied on the Rust side.
+///
+/// # Arguments
+///
+/// * `alpha_data` - Alpha channel as u8 array (0-255)
+/// * `width` - Image width in pixels
+/// * `height` - Image height in pixels
+/// * `max_distance` - Maximum distance to compute (affects precision)
+/// * `seeds` - Scratch for the seed grid, `width * height` entries
+/// * `distances` - Scratch for seed distances, `width * height` entries
+/// * `out` - Receives the SDF, `width * height` bytes
+///
+/// # Errors
+///
+/// Returns [`Error::LengthMismatch`] if any buffer isn't `width * height` long.
+#[wasm_bindgen]
+pub fn generate_sdf_into(
+    alpha_data: &[u8],
+    width: u32,
+    height: u32,
+    max_distance: f32,
+    seeds: &mut [i32],
+    distances: &mut [f32],
+    out: &mut [u8],
+) -> Result<(), Error> {
+    let w = width as usize;
+    let h = height as usize;
+    let size = w * h;
+
+    check_len("alpha_data", alpha_data.len(), size)?;
+    check_len("seeds", seeds.len(), size)?;
+    check_len("distances", distances.len(), size)?;
+    check_len("out", out.len(), size)?;
+
+    let options = SdfOptions {
+        max_distance,
+        ..SdfOptions::default()
+    };
+
+    if let Some(inside) = uniform_side(alpha_data, options.threshold) {
+        let far = if inside { -max_distance } else { max_distance };
+        out.fill(encode_distance(far, max_distance));
+        return Ok(());
+    }
+
+    flood_distances(alpha_data, w, h, &options, seeds, distances);
+    encode_field(alpha_data, distances, &options, max_distance, out);
+    Ok(())
+}
+
+/// Full JFA pipeline: seed, propagate, normalize
+///
+/// The seed grid is released before the output is allocated, so peak memory
+/// is the 8 bytes/pixel of JFA scratch rather than 9 with the output on top.
+fn jump_flood_sdf(
+    alpha_data: &[u8],
+    w: usize,
+    h: usize,
+    options: &SdfOptions,
+    scale: f32,
+) -> Vec<u8> {
+    let distances = seed_distances(alpha_data, w, h, options);
+
+    let mut result = vec![0u8; w * h];
+    encode_field(alpha_data, &distances, options, scale, &mut result);
+
+    result
+}
+
+/// JFA over the sprite's bounding box plus a `max_distance` margin
+///
+/// Every edge seed lies within one pixel of the bounding box, so pixels
+/// beyond the margin are at least `max_distance` from any seed and encode
+/// as the far-field outside value. The window's own border sits in that
+/// transparent margin, so cropping introduces no spurious edges.
+fn windowed_sdf(
+    alpha_data: &[u8],
+    w: usize,
+    h: usize,
+    options: &SdfOptions,
+) -> Vec<u8> {
+    let bounds = calculate_centroid(alpha_data, w as u32, h as u32, options.threshold);
+    let scale = options.encoding_scale(w, h);
+    let margin = options.max_distance.max(0.0).ceil() as u32 + 1;
+
+    let x0 = bounds.bounds_x.saturating_sub(margin);
+    let y0 = bounds.bounds_y.saturating_sub(margin);
+    let x1 = (bounds.bounds_x + bounds.bounds_width).saturating_add(margin).min(w as u32);
+    let y1 = (bounds.bounds_y + bounds.bounds_height).saturating_add(margin).min(h as u32);
+    let (ww, wh) = ((x1 - x0) as usize, (y1 - y0) as usize);
+
+    if ww == w && wh == h {
+        return jump_flood_sdf(alpha_data, w, h, options, scale);
+    }
+
+    let window = extract_region(alpha_data, w as u32, 1, x0, y0, ww as u32, wh as u32);
+    let field = jump_flood_sdf(&window, ww, wh, options, scale);
+
+    let far = options
+        .region
+        .sentinel(false)
+        .unwrap_or_else(|| encode_distance(options.max_distance, scale));
+    let mut result = vec![far; w * h];
+    for (row, src) in field.chunks_exact(ww).enumerate() {
+        let start = (y0 as usize + row) * w + x0 as usize;
+        result[start..start + ww].copy_from_slice(src);
+    }
+
+    result
+}
+
+/// Unencoded distance from each pixel to its nearest edge seed
+///
+/// Steps 1 and 2 of the pipeline, for callers that need raw pixel
+/// distances rather than the quantized u8 field. Not clamped to
+/// `max_distance`; `f32::MAX` where there are no seeds.
+pub(crate) fn seed_distances(
+    alpha_data: &[u8],
+    w: usize,
+    h: usize,
+    options: &SdfOptions,
+) -> Vec<f32> {
+    // Initialize seed grid (-1 = no seed, otherwise index of nearest seed)
+    let mut seeds: Vec<i32> = vec![-1; w * h];
+    let mut distances: Vec<f32> = vec![f32::MAX; w * h];
+
+    flood_distances(alpha_data, w, h, options, &mut seeds, &mut distances);
+    distances
+}
+
+/// Steps 1 and 2: seed edge pixels, then jump flood nearest-seed distances
+///
+/// `seeds` and `distances` are fully overwritten, so scratch buffers can be
+/// reused across calls.
+fn flood_distances(
+    alpha_data: &[u8],
+    w: usize,
+    h: usize,
+    options: &SdfOptions,
+    seeds: &mut [i32],
+    distances: &mut [f32],
+) {
+    if options.symmetric {
+        flood_all_orientations(alpha_data, w, h, options, seeds, distances);
+        return;
+    }
+
+    // Step 1: Find edge pixels and mark as seeds
+    let offsets = if options.coverage_seeding {
+        seed_coverage(alpha_data, w, h, options.metric, seeds, distances)
+    } else {
+        seed_edges(alpha_data, w, h, options.threshold, seeds, distances);
+        Vec::new()
+    };
+
+    // Step 2: Jump Flooding Algorithm
+    jump_flood(w, h, options, &offsets, seeds, distances);
+}
+
+/// Propagate nearest seeds over the full halving schedule
+///
+/// `seeds` and `distances` must already hold the seed pixels (their own
+/// index and 0.0) with -1 and `f32::MAX` everywhere else.
+pub(crate) fn jump_flood(
+    w: usize,
+    h: usize,
+    options: &SdfOptions,
+    offsets: &[(f32, f32)],
+    seeds: &mut [i32],
+    distances: &mut [f32],
+) {
+    let mut step = (w.max(h) / 2).max(1);
+    while step >= 1 {
+        jump_pass(w, h, step, options, offsets, seeds, distances);
+        step /= 2;
+    }
+    if options.stable {
+        jump_pass(w, h, 1, options, offsets, seeds, distances);
+    }
+}
+
+/// [`flood_distances`] over all eight orientations of the grid, keeping
+/// each pixel's nearest result
+///
+/// A single JFA run's errors depend on scan order, so a pixel and its
+/// mirror image can end up with different distances. Every orientation of
+/// the mask is flooded here and mapped back, and the minimum over that set
+/// doesn't depend on how the mask was oriented to begin with. Each seed
+/// comes from the first orientation that found the nearest distance.
+fn flood_all_orientations(
+    alpha_data: &[u8],
+    w: usize,
+    h: usize,
+    options: &SdfOptions,
+    seeds: &mut [i32],
+    distances: &mut [f32],
+) {
+    let single = SdfOptions {
+        symmetric: false,
+        ..*options
+    };
+    seeds.fill(-1);
+    distances.fill(f32::MAX);
+
+    // Bit 0 flips horizontally, bit 1 vertically, bit 2 transposes
+    for orientation in 0..8 {
+        let transpose = orientation & 4 != 0;
+        let (ow, oh) = if transpose { (h, w) } else { (w, h) };
+
+        // Index in the original grid of each oriented pixel
+        let source: Vec<usize> = (0..ow * oh)
+            .map(|i| {
+                let (mut u, mut v) = (i % ow, i / ow);
+                if orientation & 1 != 0 {
+                    u = ow - 1 - u;
+                }
+                if orientation & 2 != 0 {
+                    v = oh - 1 - v;
+                }
+                if transpose { u * w + v } else { v * w + u }
+            })
+            .collect();
+
+        let oriented: Vec<u8> = source.iter().map(|&i| alpha_data[i]).collect();
+        let mut oriented_seeds = vec![-1; ow * oh];
+        let mut oriented_distances = vec![f32::MAX; ow * oh];
+        flood_distances(&oriented, ow, oh, &single, &mut oriented_seeds, &mut oriented_distances);
+
+        for (i, &idx) in source.iter().enumerate() {
+            if oriented_distances[i] < distances[idx] {
+                distances[idx] = oriented_distances[i];
+                seeds[idx] = source[oriented_seeds[i] as usize] as i32;
+            }
+        }
+    }
+}
+
+/// Seed the pixels an anti-aliased edge passes through, at sub-pixel
+/// positions estimated from coverage
+///
+/// Seeds are every partially covered pixel, plus fully opaque and fully
+/// transparent pixels that touch each other directly (a hard edge, whose
+/// two seeds then meet on the shared pixel boundary). Returns each seed's
+/// offset from its pixel center, indexed by pixel.
+fn seed_coverage(
+    alpha_data: &[u8],
+    w: usize,
+    h: usize,
+    metric: DistanceMetric,
+    seeds: &mut [i32],
+    distances: &mut [f32],
+) -> Vec<(f32, f32)> {
+    let mut offsets = vec![(0.0f32, 0.0f32); w * h];
+    let at = |x: usize, y: usize| alpha_data[y * w + x] as f32;
+
+    for y in 0..h {
+        for x in 0..w {
+            let idx = y * w + x;
+            let a = alpha_data[idx];
+
+            let neighbors = [
+                (x.wrapping_sub(1), y),
+                (x + 1, y),
+                (x, y.wrapping_sub(1)),
+                (x, y + 1),
+            ];
+            let hard_edge = (a == 0 || a == 255)
+                && neighbors
+                    .iter()
+                    .any(|&(nx, ny)| nx < w && ny < h && alpha_data[ny * w + nx] == 255 - a);
+
+            if !(hard_edge || (1..255).contains(&a)) {
+                seeds[idx] = -1;
+                distances[idx] = f32::MAX;
+                continue;
+            }
+
+            // Alpha gradient (toward the opaque side), clamped at borders
+            let gx = at((x + 1).min(w - 1), y) - at(x.saturating_sub(1), y);
+            let gy = at(x, (y + 1).min(h - 1)) - at(x, y.saturating_sub(1));
+            let len = (gx * gx + gy * gy).sqrt();
+
+            if len > 0.0 {
+                let shift = a as f32 / 255.0 - 0.5;
+                offsets[idx] = (-shift * gx / len, -shift * gy / len);
+            }
+
+            let (ox, oy) = offsets[idx];
+            seeds[idx] = idx as i32;
+            distances[idx] = metric.distance(0.0, 0.0, ox, oy);
+        }
+    }
+
+    offsets
+}
+
+/// Mark edge pixels as their own seed at distance zero, clearing the rest
+fn seed_edges(
+    alpha_data: &[u8],
+    w: usize,
+    h: usize,
+    threshold: u8,
+    seeds: &mut [i32],
+    distances: &mut [f32],
+) {
+    for y in 0..h {
+        for x in 0..w {
+            let idx = y * w + x;
+
+            // Check if this is an edge pixel (alpha transition)
+            if is_edge_pixel(alpha_data, w, h, x, y, threshold) {
+                seeds[idx] = idx as i32;
+                distances[idx] = 0.0;
+            } else {
+                seeds[idx] = -1;
+                distances[idx] = f32::MAX;
+            }
+        }
+    }
+}
+
+/// One JFA pass: adopt any nearer seed found `step` pixels away
+///
+/// `offsets` holds each seed's sub-pixel offset from its pixel center, or
+/// is empty when seeds sit exactly on pixel centers.
+fn jump_pass(
+    w: usize,
+    h: usize,
+    step: usize,
+    options: &SdfOptions,
+    offsets: &[(f32, f32)],
+    seeds: &mut [i32],
+    distances: &mut [f32],
+) {
+    let axis_only = options.jfa_neighbors == 4;
+    let stable = options.stable;
+
+    for y in 0..h {
+        for x in 0..w {
+            let idx = y * w + x;
+
+            // Check 8 (or 4 axis-aligned) neighbors at current step distance
+            for dy in [-1i32, 0, 1].iter() {
+                for dx in [-1i32, 0, 1].iter() {
+                    if *dx == 0 && *dy == 0 {
+                        continue;
+                    }
+                    if axis_only && *dx != 0 && *dy != 0 {
+                        continue;
+                    }
+
+                    let nx = x as i32 + dx * step as i32;
+                    let ny = y as i32 + dy * step as i32;
+
+                    if nx >= 0 && nx < w as i32 && ny >= 0 && ny < h as i32 {
+                        let nidx = (ny as usize) * w + (nx as usize);
+
+                        if seeds[nidx] >= 0 {
+                            let seed_idx = seeds[nidx] as usize;
+                            let (ox, oy) = offsets.get(seed_idx).copied().unwrap_or_default();
+                            let seed_x = (seed_idx % w) as f32 + ox;
+                            let seed_y = (seed_idx / w) as f32 + oy;
+
+                            let dist = options.metric.distance(x as f32, y as f32, seed_x, seed_y);
+
+                            let closer = dist < distances[idx]
+                                || (stable && dist == distances[idx] && seeds[nidx] < seeds[idx]);
+                            if closer {
+                                distances[idx] = dist;
+                                seeds[idx] = seeds[nidx];
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Step 3: Convert to signed distance and normalize by `scale` into `out`
+fn encode_field(
+    alpha_data: &[u8],
+    distances: &[f32],
+    options: &SdfOptions,
+    scale: f32,
+    out: &mut [u8],
+) {
+    let max_distance = options.max_distance;
+
+    for (idx, value) in out.iter_mut().enumerate() {
+        let inside = alpha_data[idx] >= options.threshold;
+        if let Some(sentinel) = options.region.sentinel(inside) {
+            *value = sentinel;
+            continue;
+        }
+
+        let dist = distances[idx].min(max_distance);
+
+        // Signed: negative inside, positive outside
+        let signed_dist = if inside {
+            -dist // Inside
+        } else {
+            dist // Outside
+        };
+
+        // Normalize to 0-255 with 128 as the edge
+        *value = encode_distance(signed_dist, scale);
+    }
+}
+
+/// Reusable SDF generator for per-frame use
+///
+/// Holds the JFA scratch buffers (seed grid and distances, 8 bytes/pixel)
+/// between calls so a render loop generating one field per frame only
+/// allocates the returned output.
+#[wasm_bindgen]
+pub struct SdfGenerator {
+    width: u32,
+    height: u32,
+    seeds: Vec<i32>,
+    distances: Vec<f32>,
+}
+
+#[wasm_bindgen]
+impl SdfGenerator {
+    /// Create a generator for masks of the given dimensions
+    #[wasm_bindgen(constructor)]
+    pub fn new(width: u32, height: u32) -> SdfGenerator {
+        let size = width as usize * height as usize;
+        SdfGenerator {
+            width,
+            height,
+            seeds: vec![-1; size],
+            distances: vec![f32::MAX; size],
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Generate an SDF, identical to [`generate_sdf`], reusing scratch
+    pub fn generate(&mut self, alpha_data: &[u8], max_distance: f32) -> Vec<u8> {
+        let w = self.width as usize;
+        let h = self.height as usize;
+        let options = SdfOptions {
+            max_distance,
+            ..SdfOptions::default()
+        };
+
+        if let Some(field) = uniform_field(alpha_data, w * h, &options, options.max_distance) {
+            self.seeds.fill(-1);
+            self.distances.fill(f32::MAX);
+            return field;
+        }
+
+        flood_distances(
+            alpha_data,
+            w,
+            h,
+            &options,
+            &mut self.seeds,
+            &mut self.distances,
+        );
+
+        let mut result = vec![0u8; w * h];
+        encode_field(alpha_data, &self.distances, &options, max_distance, &mut result);
+        result
+    }
+
+    /// Nearest-seed index per pixel from the last generated field (-1 where
+    /// no seed was found), to pass back to [`Self::generate_warmstart`]
+    #[wasm_bindgen(getter)]
+    pub fn seeds(&self) -> Vec<i32> {
+        self.seeds.clone()
+    }
+
+    /// Generate an SDF starting from the previous frame's converged seeds
+    ///
+    /// Every pixel inherits its nearest seed from `prev_seeds` as long as
+    /// that seed is still an edge pixel; current edges seed themselves. If
+    /// the edges are exactly those of the previous frame the field is
+    /// already converged and no JFA passes run at all. Otherwise only the
+    /// passes with steps up to `max_distance` run, since nothing farther
+    /// than that survives encoding.
+    ///
+    /// An unchanged matte produces exactly [`Self::generate`]'s output; a
+    /// changed one agrees with it to within JFA's usual pixel-level
+    /// approximation.
+    ///
+    /// # Arguments
+    ///
+    /// * `alpha_data` - Alpha channel as u8 array (0-255)
+    /// * `prev_seeds` - [`Self::seeds`] after the previous frame
+    /// * `max_distance` - Maximum distance to compute
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::LengthMismatch`] if `alpha_data` or `prev_seeds`
+    /// isn't one entry per pixel.
+    pub fn generate_warmstart(
+        &mut self,
+        alpha_data: &[u8],
+        prev_seeds: &[i32],
+        max_distance: f32,
+    ) -> Result<Vec<u8>, Error> {
+        let w = self.width as usize;
+        let h = self.height as usize;
+        let size = w * h;
+        check_len("alpha_data", alpha_data.len(), size)?;
+        check_len("prev_seeds", prev_seeds.len(), size)?;
+
+        let options = SdfOptions {
+            max_distance,
+            ..SdfOptions::default()
+        };
+
+        if let Some(field) = uniform_field(alpha_data, w * h, &options, options.max_distance) {
+            self.seeds.fill(-1);
+            self.distances.fill(f32::MAX);
+            return Ok(field);
+        }
+
+        seed_edges(
+            alpha_data,
+            w,
+            h,
+            options.threshold,
+            &mut self.seeds,
+            &mut self.distances,
+        );
+
+        let mut changed = false;
+        for (idx, &prev) in prev_seeds.iter().enumerate() {
+            let is_edge = self.seeds[idx] == idx as i32;
+            changed |= is_edge != (prev == idx as i32);
+            if is_edge {
+                continue;
+            }
+
+            // Inherit the previous nearest seed if it is still an edge
+            if prev >= 0 && (prev as usize) < size && self.seeds[prev as usize] == prev {
+                let (px, py) = ((prev as usize % w) as f32, (prev as usize / w) as f32);
+                self.seeds[idx] = prev;
+                self.distances[idx] =
+                    options.metric.distance((idx % w) as f32, (idx / w) as f32, px, py);
+            }
+        }
+
+        if changed {
+            let reach = (max_distance.max(1.0).ceil() as usize).next_power_of_two();
+            let mut step = reach.min((w.max(h) / 2).max(1));
+            while step >= 1 {
+                jump_pass(w, h, step, &options, &[], &mut self.seeds, &mut self.distances);
+                step /= 2;
+            }
+        }
+
+        let mut result = vec![0u8; size];
+        encode_field(alpha_data, &self.distances, &options, max_distance, &mut result);
+        Ok(result)
+    }
+}
+
+/// SDF generation over a sequence of frames, one frame per step
+///
+/// Lets a caller drive a long batch without passing a callback across the
+/// WASM boundary: call [`Self::step`] in a loop (typically in a worker)
+/// and read [`Self::progress`] between steps to update a progress bar.
+/// Frames share one [`SdfGenerator`], so scratch buffers are allocated
+/// once for the whole batch.
+#[wasm_bindgen]
+pub struct BatchJob {
+    generator: SdfGenerator,
+    frames: Vec<u8>,
+    max_distance: f32,
+    completed: u32,
+    output: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl BatchJob {
+    /// Queue alpha masks for SDF generation
+    ///
+    /// # Arguments
+    ///
+    /// * `frames` - Alpha masks back to back, `width * height` bytes each
+    /// * `width` - Frame width
+    /// * `height` - Frame height
+    /// * `max_distance` - Maximum distance for every field
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidArgument`] if `frames` isn't a whole number
+    /// of non-empty frames.
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        frames: Vec<u8>,
+        width: u32,
+        height: u32,
+        max_distance: f32,
+    ) -> Result<BatchJob, Error> {
+        let size = width as usize * height as usize;
+        if size == 0 || !frames.len().is_multiple_of(size) {
+            return Err(Error::InvalidArgument {
+                name: "frames",
+                reason: "must be a whole number of width * height masks",
+            });
+        }
+
+        Ok(BatchJob {
+            generator: SdfGenerator::new(width, height),
+            output: Vec::with_capacity(frames.len()),
+            frames,
+            max_distance,
+            completed: 0,
+        })
+    }
+
+    /// Number of frames in the batch
+    #[wasm_bindgen(getter)]
+    pub fn frame_count(&self) -> u32 {
+        (self.frames.len() / self.frame_size()) as u32
+    }
+
+    /// Number of frames processed so far
+    #[wasm_bindgen(getter)]
+    pub fn progress(&self) -> u32 {
+        self.completed
+    }
+
+    /// Whether every frame has been processed
+    #[wasm_bindgen(getter)]
+    pub fn done(&self) -> bool {
+        self.completed == self.frame_count()
+    }
+
+    /// Generate the next frame's SDF
+    ///
+    /// Returns `false`, doing nothing, once the batch is done.
+    pub fn step(&mut self) -> bool {
+        if self.done() {
+            return false;
+        }
+
+        let size = self.frame_size();
+        let start = self.completed as usize * size;
+        let field = self
+            .generator
+            .generate(&self.frames[start..start + size], self.max_distance);
+        self.output.extend_from_slice(&field);
+        self.completed += 1;
+        true
+    }
+
+    /// Fields generated so far, back to back in frame order
+    #[wasm_bindgen(getter)]
+    pub fn output(&self) -> Vec<u8> {
+        self.output.clone()
+    }
+}
+
+impl BatchJob {
+    fn frame_size(&self) -> usize {
+        self.generator.width as usize * self.generator.height as usize
+    }
+}
+
+/// Separate inner and outer distance fields
+#[wasm_bindgen]
+pub struct SplitSdf {
+    inner: Vec<u8>,
+    outer: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl SplitSdf {
+    /// Distance inside the shape: 0 at the edge and outside, growing inward
+    #[wasm_bindgen(getter)]
+    pub fn inner(&self) -> Vec<u8> {
+        self.inner.clone()
+    }
+
+    /// Distance outside the shape: 0 at the edge and inside, growing outward
+    #[wasm_bindgen(getter)]
+    pub fn outer(&self) -> Vec<u8> {
+        self.outer.clone()
+    }
+}
+
+/// Generate unsigned inner and outer distance fields in one pass
+///
+/// For layered effects (inner shadow vs outer glow) that would otherwise
+/// decode the sign in the shader. Each field maps 0..`max_distance` to
+/// 0..255.
+///
+/// # Arguments
+///
+/// * `alpha_data` - Alpha channel as u8 array (0-255)
+/// * `width` - Image width in pixels
+/// * `height` - Image height in pixels
+/// * `max_distance` - Distance that maps to 255
 #[wasm_bindgen]
-pub fn generate_sdf(
+pub fn generate_sdf_split(
     alpha_data: &[u8],
     width: u32,
     height: u32,
     max_distance: f32,
-) -> Vec<u8> {
+) -> SplitSdf {
     let w = width as usize;
     let h = height as usize;
     let size = w * h;
+    let options = SdfOptions {
+        max_distance,
+        ..SdfOptions::default()
+    };
 
-    // Initialize seed grid (-1 = no seed, otherwise index of nearest seed)
     let mut seeds: Vec<i32> = vec![-1; size];
     let mut distances: Vec<f32> = vec![f32::MAX; size];
+    flood_distances(alpha_data, w, h, &options, &mut seeds, &mut distances);
+    drop(seeds);
 
-    // Step 1: Find edge pixels and mark as seeds
-    for y in 0..h {
-        for x in 0..w {
-            let idx = y * w + x;
-            let alpha = alpha_data[idx];
-
-            // Check if this is an edge pixel (alpha transition)
-            let is_edge = is_edge_pixel(alpha_data, w, h, x, y);
-
-            if is_edge {
-                seeds[idx] = idx as i32;
-                distances[idx] = 0.0;
-            }
+    let mut inner = vec![0u8; size];
+    let mut outer = vec![0u8; size];
+    for idx in 0..size {
+        let value = to_u8(distances[idx].min(max_distance) / max_distance * 255.0);
+        if alpha_data[idx] >= options.threshold {
+            inner[idx] = value;
+        } else {
+            outer[idx] = value;
         }
     }
 
-    // Step 2: Jump Flooding Algorithm
-    let mut step = (w.max(h) / 2).max(1);
-    while step >= 1 {
-        for y in 0..h {
-            for x in 0..w {
-                let idx = y * w + x;
-
-                // Check 8 neighbors at current step distance
-                for dy in [-1i32, 0, 1].iter() {
-                    for dx in [-1i32, 0, 1].iter() {
-                        if *dx == 0 && *dy == 0 {
-                            continue;
-                        }
+    SplitSdf { inner, outer }
+}
+
+/// Cut a sub-rectangle out of an already generated SDF
+///
+/// Each value is the distance to the nearest edge in the full frame, so a
+/// crop is a valid field as is: pixels near the new border may measure to
+/// edges that lie outside the crop, but those distances are still
+/// correct. Regenerating from the cropped alpha would instead treat the
+/// crop border as the end of the world and lose those edges.
+///
+/// # Arguments
+///
+/// * `sdf` - Encoded SDF, `width * height` bytes
+/// * `width` - Field width
+/// * `height` - Field height
+/// * `rx`, `ry` - Top-left corner of the crop
+/// * `rw`, `rh` - Size of the crop
+///
+/// # Errors
+///
+/// Returns [`Error::LengthMismatch`] if `sdf` doesn't match the
+/// dimensions, or [`Error::RegionOutOfBounds`] if the crop leaves the
+/// field.
+#[wasm_bindgen]
+pub fn crop_sdf(
+    sdf: &[u8],
+    width: u32,
+    height: u32,
+    rx: u32,
+    ry: u32,
+    rw: u32,
+    rh: u32,
+) -> Result<Vec<u8>, Error> {
+    check_len("sdf", sdf.len(), width as usize * height as usize)?;
+    check_region(rx, ry, rw, rh, width, height)?;
+
+    Ok(extract_region(sdf, width, 1, rx, ry, rw, rh))
+}
+
+/// Blend two distance fields for temporal smoothing
+///
+/// Decodes both fields to signed distances, interpolates them, and
+/// re-encodes. Both must be generated with the same `max_distance`, which
+/// cancels out of the linear encoding and so isn't needed here.
+///
+/// # Arguments
+///
+/// * `prev` - Previous frame's encoded SDF
+/// * `curr` - Current frame's encoded SDF
+/// * `alpha` - Blend factor (0.0 = `prev`, 1.0 = `curr`)
+///
+/// # Errors
+///
+/// Returns [`Error::LengthMismatch`] if the fields differ in length.
+#[wasm_bindgen]
+pub fn blend_sdf(prev: &[u8], curr: &[u8], alpha: f32) -> Result<Vec<u8>, Error> {
+    check_len("curr", curr.len(), prev.len())?;
+
+    Ok(prev
+        .iter()
+        .zip(curr.iter())
+        .map(|(&p, &c)| {
+            let p = decode_distance(p, 1.0);
+            let c = decode_distance(c, 1.0);
+            let blended = p + (c - p) * alpha;
+            to_u8(blended * 127.0 + 128.0)
+        })
+        .collect())
+}
 
-                        let nx = x as i32 + dx * step as i32;
-                        let ny = y as i32 + dy * step as i32;
+/// Boolean operation for [`combine_sdf`]
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BoolOp {
+    /// Inside either shape: `min(a, b)`
+    Union,
+    /// Inside both shapes: `max(a, b)`
+    Intersection,
+    /// Inside `a` but not `b`: `max(a, -b)`
+    Subtraction,
+}
 
-                        if nx >= 0 && nx < w as i32 && ny >= 0 && ny < h as i32 {
-                            let nidx = (ny as usize) * w + (nx as usize);
+/// Compose two shapes directly on their distance fields
+///
+/// Decodes both fields, combines the signed distances with `op` and
+/// re-encodes, so composite silhouettes need no raster round trip or new
+/// JFA pass. The result's zero crossing is exactly the composite boundary.
+/// Outside a union (and inside an intersection) the distances are exact;
+/// elsewhere they are lower bounds, which is still correct for
+/// thresholding and soft edges near the boundary.
+///
+/// # Arguments
+///
+/// * `a` - First encoded SDF
+/// * `b` - Second encoded SDF, same size and `max_distance` as `a`
+/// * `op` - Boolean operation
+/// * `max_distance` - The `max_distance` both fields were generated with
+///
+/// # Errors
+///
+/// Returns [`Error::LengthMismatch`] if the fields differ in length.
+#[wasm_bindgen]
+pub fn combine_sdf(a: &[u8], b: &[u8], op: BoolOp, max_distance: f32) -> Result<Vec<u8>, Error> {
+    check_len("b", b.len(), a.len())?;
 
-                            if seeds[nidx] >= 0 {
-                                let seed_idx = seeds[nidx] as usize;
-                                let seed_x = seed_idx % w;
-                                let seed_y = seed_idx / w;
+    Ok(a
+        .iter()
+        .zip(b.iter())
+        .map(|(&a, &b)| {
+            let da = decode_distance(a, max_distance);
+            let db = decode_distance(b, max_distance);
+            let combined = match op {
+                BoolOp::Union => da.min(db),
+                BoolOp::Intersection => da.max(db),
+                BoolOp::Subtraction => da.max(-db),
+            };
+            encode_distance(combined, max_distance)
+        })
+        .collect())
+}
 
-                                let dist = euclidean_distance(x, y, seed_x, seed_y);
+/// Classify a mask that lies entirely on one side of `threshold`
+///
+/// Returns `Some(true)` if every pixel is inside, `Some(false)` if every
+/// pixel is outside, and `None` if the mask has at least one transition.
+fn uniform_side(alpha: &[u8], threshold: u8) -> Option<bool> {
+    let inside = *alpha.first()? >= threshold;
+    alpha
+        .iter()
+        .all(|&a| (a >= threshold) == inside)
+        .then_some(inside)
+}
 
-                                if dist < distances[idx] {
-                                    distances[idx] = dist;
-                                    seeds[idx] = seeds[nidx];
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        step /= 2;
+/// Far-field SDF for a `size`-pixel mask with no edges, or `None` if it
+/// has any
+///
+/// Every pixel of a uniformly inside or outside mask sits at the clamped
+/// far-field distance, which is what the full pipeline would produce.
+/// Only the first `size` values are examined; a shorter buffer is left to
+/// the full pipeline.
+fn uniform_field(
+    alpha_data: &[u8],
+    size: usize,
+    options: &SdfOptions,
+    scale: f32,
+) -> Option<Vec<u8>> {
+    let max_distance = options.max_distance;
+    let inside = uniform_side(alpha_data.get(..size)?, options.threshold)?;
+    let far = if inside { -max_distance } else { max_distance };
+    let value = options
+        .region
+        .sentinel(inside)
+        .unwrap_or_else(|| encode_distance(far, scale));
+    Some(vec![value; size])
+}
+
+/// Encode a signed distance in pixels as an SDF byte (128 = edge), with
+/// `scale` pixels spanning 127 steps
+fn encode_distance(signed_dist: f32, scale: f32) -> u8 {
+    to_u8((signed_dist / scale) * 127.0 + 128.0)
+}
+
+/// Convert an f32 to IEEE 754 binary16 bits, rounding to nearest even
+fn f32_to_f16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exp = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x007f_ffff;
+
+    // NaN and infinity
+    if exp == 0xff {
+        return sign | 0x7c00 | if mantissa != 0 { 0x0200 } else { 0 };
     }
 
-    // Step 3: Convert to signed distance and normalize
-    let mut result = vec![0u8; size];
+    let half_exp = exp - 127 + 15;
+    if half_exp >= 0x1f {
+        return sign | 0x7c00; // Overflow to infinity
+    }
 
-    for y in 0..h {
-        for x in 0..w {
-            let idx = y * w + x;
-            let alpha = alpha_data[idx];
-            let dist = distances[idx].min(max_distance);
+    if half_exp <= 0 {
+        // Subnormal (or zero): shift the implicit leading bit into place
+        if half_exp < -10 {
+            return sign;
+        }
+        let full = mantissa | 0x0080_0000;
+        let shift = (14 - half_exp) as u32;
+        let mut half = full >> shift;
+        let rest = full & ((1 << shift) - 1);
+        let halfway = 1 << (shift - 1);
+        if rest > halfway || (rest == halfway && half & 1 == 1) {
+            half += 1;
+        }
+        return sign | half as u16;
+    }
 
-            // Signed: negative inside, positive outside
-            let signed_dist = if alpha > 127 {
-                -dist // Inside
-            } else {
-                dist // Outside
-            };
+    let mut half = ((half_exp as u32) << 10) | (mantissa >> 13);
+    let rest = mantissa & 0x1fff;
+    if rest > 0x1000 || (rest == 0x1000 && half & 1 == 1) {
+        // Carries into the exponent (and up to infinity) correctly
+        half += 1;
+    }
+    sign | half as u16
+}
+
+/// Decode an SDF byte back to a signed distance in pixels
+///
+/// Inverse of the normalization in [`generate_sdf`]: negative inside,
+/// positive outside, zero on an edge seed.
+pub(crate) fn decode_distance(value: u8, max_distance: f32) -> f32 {
+    (value as f32 - 128.0) / 127.0 * max_distance
+}
 
-            // Normalize to 0-255 with 128 as the edge
-            let normalized = ((signed_dist / max_distance) * 127.0 + 128.0)
-                .clamp(0.0, 255.0) as u8;
+/// Count the edge seeds the SDF init phase would place
+///
+/// Zero seeds means the mask is uniform (all inside or all outside) and
+/// any SDF generated from it is meaningless. Seeds are placed on both sides
+/// of each alpha transition, so a closed silhouette yields roughly twice its
+/// perimeter length.
+///
+/// # Arguments
+///
+/// * `alpha_data` - Alpha channel as u8 array
+/// * `width` - Image width
+/// * `height` - Image height
+/// * `threshold` - Alpha threshold (0-255) for considering a pixel "opaque"
+#[wasm_bindgen]
+pub fn count_edge_seeds(
+    alpha_data: &[u8],
+    width: u32,
+    height: u32,
+    threshold: u8,
+) -> u32 {
+    let w = width as usize;
+    let h = height as usize;
 
-            result[idx] = normalized;
+    let mut count = 0u32;
+    for y in 0..h {
+        for x in 0..w {
+            if is_edge_pixel(alpha_data, w, h, x, y, threshold) {
+                count += 1;
+            }
         }
     }
 
-    result
+    count
 }
 
 /// Check if a pixel is on the edge (alpha transition)
-fn is_edge_pixel(alpha: &[u8], w: usize, h: usize, x: usize, y: usize) -> bool {
+pub(crate) fn is_edge_pixel(
+    alpha: &[u8],
+    w: usize,
+    h: usize,
+    x: usize,
+    y: usize,
+    threshold: u8,
+) -> bool {
     let idx = y * w + x;
-    let current = alpha[idx] > 127;
+    let current = alpha[idx] >= threshold;
 
     // Check 4-connected neighbors
     let neighbors = [
@@ -145,7 +1468,7 @@ fn is_edge_pixel(alpha: &[u8], w: usize, h: usize, x: usize, y: usize) -> bool {
     for (nx, ny) in neighbors.iter() {
         if *nx < w && *ny < h {
             let nidx = ny * w + nx;
-            let neighbor = alpha[nidx] > 127;
+            let neighbor = alpha[nidx] >= threshold;
             if current != neighbor {
                 return true;
             }
@@ -155,16 +1478,10 @@ fn is_edge_pixel(alpha: &[u8], w: usize, h: usize, x: usize, y: usize) -> bool {
     false
 }
 
-/// Euclidean distance between two points
-fn euclidean_distance(x1: usize, y1: usize, x2: usize, y2: usize) -> f32 {
-    let dx = x1 as f32 - x2 as f32;
-    let dy = y1 as f32 - y2 as f32;
-    (dx * dx + dy * dy).sqrt()
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_util::disc;
 
     #[test]
     fn test_sdf_empty() {
@@ -183,4 +1500,613 @@ mod tests {
             assert!(*v <= 128);
         }
     }
+
+    #[test]
+    fn test_uniform_fast_path() {
+        for value in [0u8, 255] {
+            let alpha = vec![value; 36];
+            let fast = generate_sdf(&alpha, 6, 6, 10.0);
+            let options = SdfOptions {
+                max_distance: 10.0,
+                ..SdfOptions::default()
+            };
+            let full = jump_flood_sdf(&alpha, 6, 6, &options, options.max_distance);
+            assert_eq!(fast, full);
+
+            // Sized by the dimensions, not the buffer
+            let mut longer = alpha.clone();
+            longer.extend_from_slice(&[value; 10]);
+            assert_eq!(generate_sdf(&longer, 6, 6, 10.0), fast);
+        }
+
+        // A uniform frame clears the generator's seeds from the last one
+        let mut generator = SdfGenerator::new(8, 8);
+        let half: Vec<u8> = (0..64).map(|i| if i % 8 < 4 { 255 } else { 0 }).collect();
+        generator.generate(&half, 8.0);
+        assert!(generator.seeds().iter().any(|&s| s >= 0));
+        generator.generate(&[0; 64], 8.0);
+        assert!(generator.seeds().iter().all(|&s| s == -1));
+    }
+
+    #[test]
+    fn test_outside_only_region() {
+        let size = 20usize;
+        let alpha = disc((size, size), (9.5, 9.5), 6.0);
+        let full = generate_sdf(&alpha, size as u32, size as u32, 16.0);
+        let options = SdfOptions {
+            max_distance: 16.0,
+            region: SdfRegion::OutsideOnly,
+            ..SdfOptions::default()
+        };
+        let outer = generate_sdf_opts(&alpha, size as u32, size as u32, &options);
+
+        for i in 0..size * size {
+            if alpha[i] >= 128 {
+                assert_eq!(outer[i], 0, "interior pixel {}", i);
+            } else {
+                assert_eq!(outer[i], full[i], "exterior pixel {}", i);
+            }
+        }
+    }
+
+    #[test]
+    fn test_windowed_matches_full() {
+        // Small disc off-center in a large canvas
+        let size = 96usize;
+        let alpha = disc((size, size), (30.0, 24.0), 5.0);
+
+        let options = SdfOptions {
+            max_distance: 8.0,
+            ..SdfOptions::default()
+        };
+        let full = generate_sdf_opts(&alpha, size as u32, size as u32, &options);
+        let windowed = generate_sdf_opts(
+            &alpha,
+            size as u32,
+            size as u32,
+            &SdfOptions {
+                windowed: true,
+                ..options
+            },
+        );
+
+        // JFA is approximate and its error depends on the grid size, so
+        // allow half a pixel (8 steps at this max_distance) of difference
+        for i in 0..size * size {
+            let diff = windowed[i].abs_diff(full[i]);
+            assert!(diff <= 8, "pixel {}: {} vs {}", i, windowed[i], full[i]);
+        }
+
+        // Far field outside the window is exact
+        assert_eq!(windowed[size * size - 1], full[size * size - 1]);
+    }
+
+    #[test]
+    fn test_schedule_matches_default() {
+        let size = 24usize;
+        let alpha = disc((size, size), (11.0, 13.0), 7.0);
+
+        // Built-in schedule for a 24x24 grid: 12, 6, 3, 1
+        let expected = generate_sdf(&alpha, size as u32, size as u32, 16.0);
+        let scheduled =
+            generate_sdf_schedule(&alpha, size as u32, size as u32, 16.0, &[12, 6, 3, 1]);
+        assert_eq!(scheduled, expected);
+
+        // An extra final pass (JFA+1) can only move pixels closer to a seed
+        let plus_one =
+            generate_sdf_schedule(&alpha, size as u32, size as u32, 16.0, &[12, 6, 3, 1, 1]);
+        for i in 0..size * size {
+            let before = decode_distance(expected[i], 16.0).abs();
+            assert!(decode_distance(plus_one[i], 16.0).abs() <= before);
+        }
+    }
+
+    #[test]
+    fn test_f16_output() {
+        // Binary16 bits back to f32 (normal numbers and zero only)
+        fn f16_to_f32(bits: u16) -> f32 {
+            let sign = if bits & 0x8000 != 0 { -1.0 } else { 1.0 };
+            let exp = ((bits >> 10) & 0x1f) as i32;
+            let mantissa = (bits & 0x3ff) as f32;
+            if exp == 0 {
+                return sign * mantissa * 2f32.powi(-24);
+            }
+            sign * (1.0 + mantissa / 1024.0) * 2f32.powi(exp - 15)
+        }
+
+        for value in [0.0f32, 1.0, -2.5, 3.0f32.sqrt(), -31.75, 1000.3, 1e-5] {
+            let round_trip = f16_to_f32(f32_to_f16(value));
+            assert!(
+                (round_trip - value).abs() <= value.abs() / 2048.0 + 3e-8,
+                "{} -> {}",
+                value,
+                round_trip
+            );
+        }
+        assert_eq!(f32_to_f16(f32::INFINITY), 0x7c00);
+        assert_eq!(f32_to_f16(1e6), 0x7c00);
+
+        // Left half opaque: seeds at x = 7 and 8 of a 16-wide row
+        let alpha: Vec<u8> = (0..16 * 4).map(|i| if i % 16 < 8 { 255 } else { 0 }).collect();
+        let field = generate_sdf_f16(&alpha, 16, 4, 4.0);
+        let row: Vec<f32> = field[16..32].iter().map(|&b| f16_to_f32(b)).collect();
+        assert_eq!(row[5], -2.0);
+        assert_eq!(row[7], 0.0);
+        assert_eq!(row[10], 2.0);
+        assert_eq!(row[0], -4.0);
+        assert_eq!(row[15], 4.0);
+    }
+
+    #[test]
+    fn test_coverage_seeding_edge_position() {
+        // Opaque for x < 8, then a pixel 20% covered, then transparent.
+        // With pixel i centered at i, the true edge is at x = 7.7.
+        let (w, h) = (16usize, 4usize);
+        let alpha: Vec<u8> = (0..w * h)
+            .map(|i| match i % w {
+                0..=7 => 255,
+                8 => 51,
+                _ => 0,
+            })
+            .collect();
+        let options = SdfOptions {
+            max_distance: 8.0,
+            coverage_seeding: true,
+            ..SdfOptions::default()
+        };
+
+        let distances = seed_distances(&alpha, w, h, &options);
+        let signed = |x: usize| {
+            let d = distances[w + x];
+            if alpha[w + x] >= options.threshold { -d } else { d }
+        };
+        assert!((signed(7) + 0.7).abs() < 1e-5);
+        assert!((signed(8) - 0.3).abs() < 1e-5);
+
+        // Interpolated zero crossing between pixels 7 and 8
+        let crossing = 7.0 + -signed(7) / (signed(8) - signed(7));
+        assert!((crossing - 7.7).abs() < 1e-5, "zero crossing at {}", crossing);
+
+        // Hard edges still meet halfway between the two pixels
+        let hard: Vec<u8> = (0..w * h).map(|i| if i % w < 8 { 255 } else { 0 }).collect();
+        let distances = seed_distances(&hard, w, h, &options);
+        assert_eq!(distances[w + 7], 0.5);
+        assert_eq!(distances[w + 8], 0.5);
+    }
+
+    #[test]
+    fn test_sdf_pyramid() {
+        let size = 64usize;
+        let alpha = disc((size, size), (32.0, 32.0), 20.0);
+
+        let pyramid = generate_sdf_pyramid(&alpha, 64, 64, 32.0, 3);
+        assert_eq!(pyramid.len(), 3);
+        assert_eq!(pyramid[0].data(), generate_sdf(&alpha, 64, 64, 32.0));
+        assert_eq!((pyramid[2].width(), pyramid[2].row_pitch()), (16, 16));
+
+        // Each level's distances are about half the level above's at the
+        // same image position
+        for n in 1..3 {
+            let (fine, coarse) = (pyramid[n - 1].data(), pyramid[n].data());
+            let (fw, cw) = (pyramid[n - 1].width() as usize, pyramid[n].width() as usize);
+            for y in 0..cw {
+                for x in 0..cw {
+                    let parent = decode_distance(fine[(2 * y) * fw + 2 * x], 32.0);
+                    let child = decode_distance(coarse[y * cw + x], 32.0);
+                    assert!((child - parent / 2.0).abs() <= 1.0, "level {} ({}, {})", n, x, y);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_sdf_scale() {
+        // Opaque left strip; the top-right corner is 23/32 of the image
+        // width from the outside edge seed at 32px and 46/64 at 64px
+        let strip = |size: usize, opaque: usize| -> Vec<u8> {
+            (0..size * size)
+                .map(|i| if i % size < opaque { 255 } else { 0 })
+                .collect()
+        };
+        let corner = |alpha: &[u8], size: usize, normalize_by: SdfScale| {
+            let options = SdfOptions {
+                max_distance: 64.0,
+                normalize_by,
+                ..SdfOptions::default()
+            };
+            generate_sdf_opts(alpha, size as u32, size as u32, &options)[size - 1]
+        };
+        let (small, large) = (strip(32, 8), strip(64, 17));
+
+        // Same fraction of the image, same byte
+        let relative = corner(&small, 32, SdfScale::MaxDimension);
+        assert_eq!(relative, corner(&large, 64, SdfScale::MaxDimension));
+        assert_eq!(relative, to_u8(128.0 + 23.0 / 32.0 * 127.0));
+
+        // Absolute encodings tell the two apart
+        assert_ne!(
+            corner(&small, 32, SdfScale::MaxDistance),
+            corner(&large, 64, SdfScale::MaxDistance)
+        );
+        assert_eq!(corner(&small, 32, SdfScale::Pixels), 128 + 23);
+        assert_eq!(corner(&large, 64, SdfScale::Pixels), 128 + 46);
+    }
+
+    #[test]
+    fn test_four_neighbor_jfa() {
+        let size = 48usize;
+        let alpha = disc((size, size), (20.0, 26.0), 12.0);
+
+        let options = SdfOptions {
+            max_distance: 16.0,
+            ..SdfOptions::default()
+        };
+        let eight = seed_distances(&alpha, size, size, &options);
+        let four = seed_distances(
+            &alpha,
+            size,
+            size,
+            &SdfOptions {
+                jfa_neighbors: 4,
+                ..options
+            },
+        );
+
+        // Within a couple of pixels of the 8-neighbor result everywhere
+        for i in 0..size * size {
+            let (d8, d4) = (eight[i].min(16.0), four[i].min(16.0));
+            assert!((d4 - d8).abs() <= 2.0, "pixel {}: {} vs {}", i, d4, d8);
+        }
+    }
+
+    #[test]
+    fn test_distance_metrics() {
+        assert_eq!(DistanceMetric::Euclidean.distance(0.0, 0.0, 3.0, 4.0), 5.0);
+        assert_eq!(DistanceMetric::Manhattan.distance(0.0, 0.0, 3.0, 4.0), 7.0);
+        assert_eq!(DistanceMetric::Chebyshev.distance(0.0, 0.0, 3.0, 4.0), 4.0);
+
+        // Diagonal offsets separate the metrics in a generated field
+        let mut alpha = vec![0u8; 15 * 15];
+        alpha[7 * 15 + 7] = 255;
+        let corner = 3 * 15 + 3;
+        let manhattan = generate_sdf_metric(&alpha, 15, 15, 20.0, DistanceMetric::Manhattan);
+        let chebyshev = generate_sdf_metric(&alpha, 15, 15, 20.0, DistanceMetric::Chebyshev);
+        let euclidean = generate_sdf(&alpha, 15, 15, 20.0);
+        assert!(manhattan[corner] > euclidean[corner]);
+        assert!(euclidean[corner] > chebyshev[corner]);
+    }
+
+    #[test]
+    fn test_default_options_match() {
+        let size = 20usize;
+        let alpha: Vec<u8> = (0..size * size)
+            .map(|i| {
+                let dx = (i % size) as f32 - 9.5;
+                let dy = (i / size) as f32 - 9.5;
+                (255.0 - (dx * dx + dy * dy).sqrt() * 30.0).clamp(0.0, 255.0) as u8
+            })
+            .collect();
+
+        let options = SdfOptions::default();
+        assert_eq!(options.threshold, 128);
+        assert_eq!(options.metric, DistanceMetric::Euclidean);
+        assert_eq!(
+            generate_sdf_opts(&alpha, 20, 20, &options),
+            generate_sdf(&alpha, 20, 20, options.max_distance)
+        );
+
+        let custom = SdfOptions {
+            max_distance: 6.0,
+            ..SdfOptions::default()
+        };
+        assert_eq!(
+            generate_sdf_opts(&alpha, 20, 20, &custom),
+            generate_sdf(&alpha, 20, 20, 6.0)
+        );
+    }
+
+    #[test]
+    fn test_split_fields() {
+        let size = 21usize;
+        let alpha = disc((size, size), (10.0, 10.0), 6.0);
+
+        let split = generate_sdf_split(&alpha, size as u32, size as u32, 16.0);
+        let inner = split.inner();
+        let outer = split.outer();
+
+        let center = 10 * size + 10;
+        let corner = 0;
+        assert_eq!(inner.iter().max(), Some(&inner[center]));
+        assert_eq!(outer[center], 0);
+        assert_eq!(outer.iter().max(), Some(&outer[corner]));
+        assert_eq!(inner[corner], 0);
+    }
+
+    #[test]
+    fn test_generator_matches() {
+        let size = 24usize;
+        let disc = disc((size, size), (12.0, 12.0), 7.0);
+        let bar: Vec<u8> = (0..size * size)
+            .map(|i| if (4..8).contains(&(i % size)) { 255 } else { 0 })
+            .collect();
+
+        // Scratch from the first frame must not leak into the second
+        let mut generator = SdfGenerator::new(size as u32, size as u32);
+        for alpha in [&disc, &bar, &disc] {
+            let expected = generate_sdf(alpha, size as u32, size as u32, 8.0);
+            assert_eq!(generator.generate(alpha, 8.0), expected);
+        }
+    }
+
+    #[test]
+    fn test_warmstart_matches_cold() {
+        let size = 32usize;
+        let disc_at = |cx: f32| disc((size, size), (cx, 16.0), 8.0);
+        let (first, moved) = (disc_at(15.0), disc_at(17.0));
+
+        let mut generator = SdfGenerator::new(size as u32, size as u32);
+        let cold = generator.generate(&first, 8.0);
+        let prev = generator.seeds();
+
+        // Unchanged matte: identical to the cold start
+        let warm = generator.generate_warmstart(&first, &prev, 8.0).unwrap();
+        assert_eq!(warm, cold);
+        assert_eq!(generator.seeds(), prev);
+
+        // Moved matte: agrees with a cold start up to JFA approximation
+        let warm = generator.generate_warmstart(&moved, &prev, 8.0).unwrap();
+        let cold = generate_sdf(&moved, size as u32, size as u32, 8.0);
+        for i in 0..size * size {
+            assert!(warm[i].abs_diff(cold[i]) <= 8, "pixel {}: {} vs {}", i, warm[i], cold[i]);
+        }
+
+        assert!(generator.generate_warmstart(&first, &prev[1..], 8.0).is_err());
+    }
+
+    #[test]
+    fn test_generate_into() {
+        let size = 16usize;
+        let alpha: Vec<u8> = (0..size * size)
+            .map(|i| if (i % size) + (i / size) < 12 { 255 } else { 0 })
+            .collect();
+
+        let mut seeds = vec![0i32; size * size];
+        let mut distances = vec![0.0f32; size * size];
+        let mut out = vec![0u8; size * size];
+        generate_sdf_into(&alpha, 16, 16, 6.0, &mut seeds, &mut distances, &mut out).unwrap();
+        assert_eq!(out, generate_sdf(&alpha, 16, 16, 6.0));
+
+        let mut short = vec![0u8; size * size - 1];
+        let err = generate_sdf_into(&alpha, 16, 16, 6.0, &mut seeds, &mut distances, &mut short);
+        assert_eq!(
+            err,
+            Err(Error::LengthMismatch {
+                buffer: "out",
+                expected: 256,
+                actual: 255,
+            })
+        );
+    }
+
+    #[test]
+    fn test_blend_sdf() {
+        let prev = vec![0u8, 100, 128, 200, 255];
+        let curr = vec![255u8, 160, 128, 90, 0];
+
+        assert_eq!(blend_sdf(&prev, &curr, 0.0).unwrap(), prev);
+        assert_eq!(blend_sdf(&prev, &curr, 1.0).unwrap(), curr);
+
+        let mid = blend_sdf(&prev, &curr, 0.5).unwrap();
+        for i in 0..prev.len() {
+            let expected = (decode_distance(prev[i], 8.0) + decode_distance(curr[i], 8.0)) / 2.0;
+            let actual = decode_distance(mid[i], 8.0);
+            assert!((actual - expected).abs() <= 0.5 * 8.0 / 127.0, "index {}", i);
+        }
+
+        assert!(blend_sdf(&prev, &curr[..4], 0.5).is_err());
+    }
+
+    #[test]
+    fn test_combine_sdf() {
+        // Two overlapping discs whose union is a peanut
+        let size = 48usize;
+        let disc_at = |cx: f32| disc((size, size), (cx, 24.0), 10.0);
+        let (left, right) = (disc_at(17.0), disc_at(31.0));
+        let sdf = |alpha: &[u8]| generate_sdf(alpha, size as u32, size as u32, 16.0);
+
+        let union = combine_sdf(&sdf(&left), &sdf(&right), BoolOp::Union, 16.0).unwrap();
+        let merged: Vec<u8> = left.iter().zip(&right).map(|(&l, &r)| l.max(r)).collect();
+        let reference = sdf(&merged);
+
+        for i in 0..size * size {
+            if merged[i] == 255 {
+                // Inside the peanut, including the waist between the discs
+                assert!(union[i] <= 128, "pixel {} should be inside", i);
+            } else {
+                // Outside, the merged boundary matches the unioned raster
+                assert!(union[i].abs_diff(reference[i]) <= 2, "pixel {}", i);
+            }
+        }
+        assert!(union[24 * size + 24] < 128);
+
+        // The waist is inside both discs only for the intersection
+        let both = combine_sdf(&sdf(&left), &sdf(&right), BoolOp::Intersection, 16.0).unwrap();
+        let cut = combine_sdf(&sdf(&left), &sdf(&right), BoolOp::Subtraction, 16.0).unwrap();
+        assert!(both[24 * size + 24] < 128 && both[24 * size + 12] > 128);
+        assert!(cut[24 * size + 12] < 128 && cut[24 * size + 24] > 128);
+
+        assert!(combine_sdf(&union, &union[1..], BoolOp::Union, 16.0).is_err());
+    }
+
+    #[test]
+    fn test_edge_seed_count() {
+        assert_eq!(count_edge_seeds(&[0u8; 64], 8, 8, 128), 0);
+        assert_eq!(count_edge_seeds(&[255u8; 64], 8, 8, 128), 0);
+
+        // Disc of radius 10: one ring of seeds each side of the silhouette
+        let size = 32usize;
+        let alpha = disc((size, size), (16.0, 16.0), 10.0);
+
+        let perimeter = 2.0 * std::f32::consts::PI * 10.0;
+        let seeds = count_edge_seeds(&alpha, size as u32, size as u32, 128) as f32;
+        assert!(seeds > perimeter * 1.5 && seeds < perimeter * 2.5, "{}", seeds);
+    }
+
+    #[test]
+    fn test_crop_sdf() {
+        let (w, h) = (24usize, 20usize);
+        let alpha: Vec<u8> = (0..w * h)
+            .map(|i| if (6..14).contains(&(i % w)) && (5..12).contains(&(i / w)) { 255 } else { 0 })
+            .collect();
+        let sdf = generate_sdf(&alpha, w as u32, h as u32, 8.0);
+
+        // A crop that cuts off the shape's left edge
+        let (rx, ry, rw, rh) = (9usize, 3usize, 10usize, 12usize);
+        let crop = crop_sdf(&sdf, w as u32, h as u32, rx as u32, ry as u32, rw as u32, rh as u32).unwrap();
+        assert_eq!(crop.len(), rw * rh);
+        for y in 0..rh {
+            assert_eq!(crop[y * rw..(y + 1) * rw], sdf[(ry + y) * w + rx..(ry + y) * w + rx + rw]);
+        }
+
+        assert!(crop_sdf(&sdf, w as u32, h as u32, 20, 0, 5, 1).is_err());
+        assert!(crop_sdf(&sdf[1..], w as u32, h as u32, 0, 0, 1, 1).is_err());
+    }
+
+    #[test]
+    fn test_batch_job_progress() {
+        // Three frames of a square sliding right
+        let (w, h) = (16usize, 12usize);
+        let frame = |offset: usize| -> Vec<u8> {
+            (0..w * h)
+                .map(|i| if (3 + offset..8 + offset).contains(&(i % w)) && (3..9).contains(&(i / w)) { 255 } else { 0 })
+                .collect()
+        };
+        let frames: Vec<u8> = (0..3).flat_map(|f| frame(f * 2)).collect();
+
+        let mut job = BatchJob::new(frames, w as u32, h as u32, 8.0).unwrap();
+        assert_eq!((job.frame_count(), job.progress()), (3, 0));
+
+        let mut seen = Vec::new();
+        while job.step() {
+            seen.push(job.progress());
+        }
+        assert_eq!(seen, [1, 2, 3]);
+        assert!(job.done());
+        assert!(!job.step());
+        assert_eq!(job.progress(), 3);
+
+        // Same fields as generating each frame on its own
+        let output = job.output();
+        for f in 0..3 {
+            let expected = generate_sdf(&frame(f * 2), w as u32, h as u32, 8.0);
+            assert_eq!(output[f * w * h..(f + 1) * w * h], expected[..], "frame {}", f);
+        }
+
+        assert!(BatchJob::new(vec![0; w * h + 1], w as u32, h as u32, 8.0).is_err());
+        assert!(BatchJob::new(Vec::new(), 0, 0, 8.0).is_err());
+    }
+
+    #[test]
+    fn test_sdf_degenerate_sizes() {
+        // 1×1: just the pixel's classification, at full distance
+        assert_eq!(generate_sdf(&[255], 1, 1, 8.0), [1]);
+        assert_eq!(generate_sdf(&[0], 1, 1, 8.0), [255]);
+        assert_eq!(SdfGenerator::new(1, 1).generate(&[200], 8.0), [1]);
+
+        // 0-sized: empty output
+        assert!(generate_sdf(&[], 0, 0, 8.0).is_empty());
+        assert!(generate_sdf(&[], 0, 7, 8.0).is_empty());
+        assert!(generate_sdf_pyramid(&[], 0, 0, 8.0, 4).iter().all(|l| l.data().is_empty()));
+    }
+
+    #[test]
+    fn test_symmetric_cross() {
+        // Plus sign centered in an odd-sized mask
+        let size = 41usize;
+        let alpha: Vec<u8> = (0..size * size)
+            .map(|i| {
+                let (x, y) = (i % size, i / size);
+                let bar = |v: usize| (17..24).contains(&v);
+                let span = |v: usize| (6..35).contains(&v);
+                if (bar(x) && span(y)) || (bar(y) && span(x)) { 255 } else { 0 }
+            })
+            .collect();
+
+        let options = SdfOptions {
+            max_distance: 16.0,
+            stable: true,
+            ..SdfOptions::default()
+        };
+        let sdf = generate_sdf_opts(&alpha, size as u32, size as u32, &options);
+        for y in 0..size {
+            for x in 0..size {
+                assert_eq!(sdf[y * size + x], sdf[y * size + size - 1 - x], "({}, {})", x, y);
+            }
+        }
+
+        // Otherwise the same field, to within the pixel of JFA error the
+        // extra pass corrects (about 8 levels at this max_distance)
+        let plain = generate_sdf(&alpha, size as u32, size as u32, 16.0);
+        assert!(sdf.iter().zip(&plain).all(|(&a, &b)| a.abs_diff(b) <= 8));
+
+        // `symmetric` keeps every flip and the transpose, on the cross and
+        // on an even-sized disc centered between pixels
+        let options = SdfOptions {
+            max_distance: 16.0,
+            symmetric: true,
+            ..SdfOptions::default()
+        };
+        let sdf = generate_sdf_opts(&alpha, size as u32, size as u32, &options);
+        assert_dihedral_symmetric(&sdf, size);
+
+        let size = 40usize;
+        let disc = disc((size, size), (19.5, 19.5), 12.0);
+        let sdf = generate_sdf_opts(&disc, size as u32, size as u32, &options);
+        assert_dihedral_symmetric(&sdf, size);
+    }
+
+    /// Assert a square field is unchanged by horizontal and vertical flips
+    /// and by transposition
+    fn assert_dihedral_symmetric(sdf: &[u8], size: usize) {
+        for y in 0..size {
+            for x in 0..size {
+                let v = sdf[y * size + x];
+                assert_eq!(v, sdf[y * size + size - 1 - x], "h-flip ({}, {})", x, y);
+                assert_eq!(v, sdf[(size - 1 - y) * size + x], "v-flip ({}, {})", x, y);
+                assert_eq!(v, sdf[x * size + y], "transpose ({}, {})", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn test_sdf_iso() {
+        // Disc whose alpha falls off linearly from radius 6 to radius 14
+        let size = 40usize;
+        let alpha: Vec<u8> = (0..size * size)
+            .map(|i| {
+                let dx = (i % size) as f32 - 20.0;
+                let dy = (i / size) as f32 - 20.0;
+                to_u8(((14.0 - (dx * dx + dy * dy).sqrt()) / 8.0).clamp(0.0, 1.0) * 255.0)
+            })
+            .collect();
+
+        let half = generate_sdf_iso(&alpha, size as u32, size as u32, 16.0, 0.5);
+        let quarter = generate_sdf_iso(&alpha, size as u32, size as u32, 16.0, 0.25);
+        assert_eq!(half, generate_sdf(&alpha, size as u32, size as u32, 16.0));
+
+        // Signed by the iso level: edge pixels on either side encode 128
+        for (i, &a) in alpha.iter().enumerate() {
+            if a >= 64 {
+                assert!(quarter[i] <= 128, "pixel {}", i);
+            } else {
+                assert!(quarter[i] >= 128, "pixel {}", i);
+            }
+        }
+        let inside = |sdf: &[u8]| sdf.iter().filter(|&&v| v < 128).count();
+        assert!(inside(&quarter) > inside(&half));
+
+        // About 2px further out along a row: radius 12 vs 10
+        let edge = |sdf: &[u8]| (20..size).find(|&x| sdf[20 * size + x] > 128).unwrap();
+        assert_eq!(edge(&quarter) - edge(&half), 2);
+    }
 }
@@ -0,0 +1,18 @@
+//! Test Fixtures
+//!
+//! Masks shared by the unit tests of several modules.
+
+/// Hard-edged disc mask: 255 within `radius` of `center`, 0 elsewhere
+///
+/// `size` is `(width, height)`; `center` is in pixel coordinates, so
+/// `(size - 1) / 2` centers the disc between pixels on an even grid.
+pub(crate) fn disc(size: (usize, usize), center: (f32, f32), radius: f32) -> Vec<u8> {
+    let (w, h) = size;
+    (0..w * h)
+        .map(|i| {
+            let dx = (i % w) as f32 - center.0;
+            let dy = (i / w) as f32 - center.1;
+            if dx * dx + dy * dy <= radius * radius { 255 } else { 0 }
+        })
+        .collect()
+}